@@ -0,0 +1,114 @@
+use crate::clock;
+use crate::db;
+use crate::db::DatabaseElement;
+use crate::session;
+use crate::shared::dbt as dbt;
+
+/// Seconds a table has to wait between two alerts.
+pub const ALERT_INTERVAL: i64 = 60;
+
+/// Open alerts, oldest first, of the table or of every table.
+pub fn open_alerts(table: Option<&dbt::VirtualTableID>, db: &sled::Db) -> Result<Vec<dbt::Alert>, String> {
+
+    let mut alerts = dbt::Alert::default()
+        .get_status(db)?
+        .into_iter()
+        .filter(|alert| table.is_none_or(|table| &alert.table == table))
+        .collect::<Vec<dbt::Alert>>();
+
+    alerts.sort_by_key(|alert| (alert.created_at, alert.id));
+
+    Ok(alerts)
+
+}
+
+pub fn alert_by_id(id: dbt::AlertID, db: &sled::Db) -> Result<Option<dbt::Alert>, String> {
+    Ok(
+        dbt::Alert::get_all(db)?
+            .into_iter()
+            .find(|alert| alert.id == id)
+    )
+}
+
+/// Raises an alert for the table and marks the table as needing
+/// attention. An alert of the same kind that is still open is
+/// returned instead of raising it again, otherwise a table that
+/// raised an alert less than [`ALERT_INTERVAL`] seconds ago is told
+/// how many seconds to wait.
+pub fn raise(
+    table: &dbt::VirtualTableID,
+    kind: dbt::AlertKind,
+    db: &sled::Db
+) -> Result<Result<dbt::Alert, i64>, String> {
+
+    if let Some(alert) = open_alerts(Some(table), db)?
+        .into_iter()
+        .find(|alert| alert.kind == kind)
+    {
+        return Ok(Ok(alert))
+    }
+
+    let now = clock::timestamp();
+
+    let last = dbt::Alert::get_all(db)?
+        .into_iter()
+        .filter(|alert| &alert.table == table)
+        .map(|alert| alert.created_at)
+        .max();
+
+    if let Some(last) = last {
+        if now - last < ALERT_INTERVAL {
+            return Ok(Err(ALERT_INTERVAL - (now - last)))
+        }
+    }
+
+    let alert = dbt::Alert {
+        id: db.generate_id().map_err(|err| err.to_string())?,
+        table: table.clone(),
+        kind,
+        created_at: now,
+        acknowledged_by: None,
+        acknowledged_at: None
+    };
+
+    alert.insert(db)?;
+    db::set_table_state(table, dbt::TableState::NeedsAttention, db)?;
+
+    Ok(Ok(alert))
+
+}
+
+/// Acknowledges the alert, the table goes back to being occupied (or
+/// free if no one is seated) once none of its alerts are open.
+pub fn acknowledge(mut alert: dbt::Alert, staff: &dbt::StaffID, db: &sled::Db) -> Result<dbt::Alert, String> {
+
+    if alert.acknowledged_at.is_some() {
+        return Ok(alert)
+    }
+
+    // Alerts are kept under whether they are acknowledged.
+    alert.remove(db)?;
+    alert.acknowledged_by = Some(staff.clone());
+    alert.acknowledged_at = Some(clock::timestamp());
+    alert.insert(db)?;
+
+    if open_alerts(Some(&alert.table), db)?.is_empty() {
+        let table = dbt::VirtualTable::get(
+            dbt::VirtualTable {
+                name: alert.table.clone(),
+                ..Default::default()
+            }.qualified_identifier(),
+            db
+        )?;
+        if table.is_some_and(|table| table.state == dbt::TableState::NeedsAttention) {
+            let state = match session::open_session_of_table(&alert.table, db)? {
+                Some(_) => dbt::TableState::Occupied,
+                None => dbt::TableState::Free
+            };
+            db::set_table_state(&alert.table, state, db)?;
+        }
+    }
+
+    Ok(alert)
+
+}
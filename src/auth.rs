@@ -0,0 +1,629 @@
+use std::sync::{Arc, LazyLock, Mutex};
+
+use actix_web::body::{BoxBody, MessageBody};
+use actix_web::dev::{ResourceDef, ServiceRequest, ServiceResponse};
+use actix_web::http::Method;
+use actix_web::middleware::Next;
+use actix_web::{web, HttpMessage};
+use argon2::password_hash::rand_core::{OsRng, RngCore};
+use argon2::password_hash::{PasswordHash, PasswordHasher, PasswordVerifier, SaltString};
+use argon2::Argon2;
+
+use crate::clock;
+use crate::db::DatabaseElement;
+use crate::shared::dbt as dbt;
+use crate::shared::logging::logf;
+use crate::shared::req_resp as req;
+
+/// Tokens last a shift, in seconds.
+pub const TOKEN_LIFETIME: i64 = 12 * 60 * 60;
+
+/// Failed logins in a row after which the account is locked for
+/// [`LOCKOUT`] seconds, so that a PIN can't be guessed.
+pub const MAX_FAILED_LOGINS: u32 = 5;
+pub const LOCKOUT: i64 = 5 * 60;
+
+pub const SECRET_MIN_LENGTH: usize = 4;
+
+pub enum LoginError {
+    /// Wrong name or secret, or a disabled account.
+    Invalid,
+    /// Seconds until the account can be logged into again.
+    Locked(i64),
+    Database(String),
+}
+
+impl From<String> for LoginError {
+    fn from(err: String) -> Self {
+        LoginError::Database(err)
+    }
+}
+
+/// Who a route is open to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Access {
+    /// Guests too, no token needed.
+    Public,
+    /// Any logged in staff member.
+    Staff,
+    Permission(dbt::Permission),
+    Admin,
+}
+
+/// The logged in staff member of a request, put into the request
+/// extensions by [`guard`].
+#[derive(Debug, Clone)]
+pub struct Authenticated {
+    pub staff: dbt::Staff,
+    pub token: String,
+}
+
+pub fn authority(role: dbt::Role) -> req::Authority {
+    match role {
+        dbt::Role::Manager => req::Authority::Admin,
+        dbt::Role::Waiter | dbt::Role::Kitchen => req::Authority::User,
+    }
+}
+
+const PERMISSIONS: [dbt::Permission; 10] = [
+    dbt::Permission::TakeOrders,
+    dbt::Permission::ServeOrders,
+    dbt::Permission::HandlePayments,
+    dbt::Permission::ManageReservations,
+    dbt::Permission::ManageTables,
+    dbt::Permission::ManageMenu,
+    dbt::Permission::ManageStock,
+    dbt::Permission::ManageSettings,
+    dbt::Permission::ViewReports,
+    dbt::Permission::ManageStaff,
+];
+
+pub fn role_permissions(role: dbt::Role) -> &'static [dbt::Permission] {
+    match role {
+        dbt::Role::Waiter => &[
+            dbt::Permission::TakeOrders,
+            dbt::Permission::ServeOrders,
+            dbt::Permission::HandlePayments,
+            dbt::Permission::ManageReservations,
+        ],
+        dbt::Role::Kitchen => &[
+            dbt::Permission::ServeOrders,
+        ],
+        dbt::Role::Manager => &[],
+    }
+}
+
+pub fn has_permission(staff: &dbt::Staff, permission: dbt::Permission) -> bool {
+    authority(staff.role) == req::Authority::Admin
+    || role_permissions(staff.role).contains(&permission)
+    || staff.permissions.contains(&permission)
+}
+
+pub fn allows(staff: &dbt::Staff, access: Access) -> bool {
+    match access {
+        Access::Public | Access::Staff => true,
+        Access::Permission(permission) => has_permission(staff, permission),
+        Access::Admin => authority(staff.role) == req::Authority::Admin,
+    }
+}
+
+/// Whether `staff` may create, change or remove an account of
+/// `role`. `ManageStaff` covers accounts without admin authority
+/// only, so that it can't be used to become an admin.
+pub fn may_manage(staff: &dbt::Staff, role: dbt::Role) -> bool {
+    allows(staff, Access::Admin) || authority(role) != req::Authority::Admin
+}
+
+/// Whether `staff` may turn the account `before` into `after`, by its
+/// role or its own permissions. Only admins can grant permissions they
+/// don't have themselves, ones the account already had can stay.
+pub fn may_grant(staff: &dbt::Staff, before: Option<&dbt::Staff>, after: &dbt::Staff) -> bool {
+    allows(staff, Access::Admin)
+    || PERMISSIONS
+        .into_iter()
+        .filter(|permission| has_permission(after, *permission))
+        .filter(|permission| before.is_none_or(|before| !has_permission(before, *permission)))
+        .all(|permission| has_permission(staff, permission))
+}
+
+pub fn account(staff: &dbt::Staff) -> req::StaffAccount {
+
+    req::StaffAccount {
+        name: staff.name.clone(),
+        role: staff.role,
+        authority: authority(staff.role),
+        permissions: PERMISSIONS
+            .into_iter()
+            .filter(|permission| has_permission(staff, *permission))
+            .collect(),
+        disabled: staff.disabled,
+        tables: staff.tables.clone(),
+        zones: staff.zones.clone()
+    }
+
+}
+
+/// Every route of the database server and who it is open to, the
+/// patterns as written in the route attributes of the handlers.
+/// Paths of no route here are admin only, so a new route is closed
+/// until it is added.
+pub const ROUTES: &[(&str, &str, Access)] = &[
+    ("GET", "/menu", Access::Public),
+    ("GET", "/offers", Access::Public),
+    ("GET", "/offers/{id}", Access::Public),
+    ("GET", "/categories", Access::Public),
+    ("GET", "/venue", Access::Public),
+    ("POST", "/orders", Access::Public),
+    ("GET", "/tables-{id}/orders", Access::Public),
+    ("POST", "/alerts", Access::Public),
+    ("POST", "/login", Access::Public),
+
+    ("GET", "/tables", Access::Staff),
+    ("GET", "/tables-{id}", Access::Staff),
+    ("GET", "/tables-{id}/bill", Access::Staff),
+    ("GET", "/tables-{id}/session", Access::Staff),
+    ("GET", "/offers-tables", Access::Staff),
+    ("GET", "/promotions", Access::Staff),
+    ("GET", "/stock", Access::Staff),
+    ("GET", "/tax-rates", Access::Staff),
+    ("GET", "/orders", Access::Staff),
+    ("GET", "/orders/specific", Access::Staff),
+    ("GET", "/sessions", Access::Staff),
+    ("GET", "/sessions-{id}", Access::Staff),
+    ("GET", "/reservations-{date}", Access::Staff),
+    ("GET", "/bills", Access::Staff),
+    ("GET", "/bills-{id}", Access::Staff),
+    ("GET", "/bills-{id}/split-{ways}", Access::Staff),
+    ("GET", "/receipts-{year:\\d+}-{number:\\d+}", Access::Staff),
+    ("GET", "/receipts-{year:\\d+}-{number:\\d+}/{format}", Access::Staff),
+    ("GET", "/stations", Access::Staff),
+    ("GET", "/stations-{id}/tickets", Access::Staff),
+    ("GET", "/me", Access::Staff),
+    ("GET", "/my-tables", Access::Staff),
+    ("GET", "/my-orders", Access::Staff),
+    ("GET", "/alerts", Access::Staff),
+    ("POST", "/alerts-acknowledge", Access::Staff),
+    ("POST", "/logout", Access::Staff),
+
+    ("DELETE", "/orders", Access::Permission(dbt::Permission::TakeOrders)),
+    ("POST", "/orders-accept", Access::Permission(dbt::Permission::TakeOrders)),
+    ("POST", "/orders-move", Access::Permission(dbt::Permission::TakeOrders)),
+    ("POST", "/sessions-move", Access::Permission(dbt::Permission::TakeOrders)),
+    ("POST", "/tables-merge", Access::Permission(dbt::Permission::TakeOrders)),
+    ("POST", "/tables-state", Access::Permission(dbt::Permission::TakeOrders)),
+
+    ("POST", "/orders-finish", Access::Permission(dbt::Permission::ServeOrders)),
+    ("POST", "/tickets-bump", Access::Permission(dbt::Permission::ServeOrders)),
+    ("POST", "/offers-sold-out", Access::Permission(dbt::Permission::ServeOrders)),
+
+    ("POST", "/bills", Access::Permission(dbt::Permission::HandlePayments)),
+    ("POST", "/bills-pay", Access::Permission(dbt::Permission::HandlePayments)),
+    ("POST", "/bills-close", Access::Permission(dbt::Permission::HandlePayments)),
+    ("POST", "/receipts", Access::Permission(dbt::Permission::HandlePayments)),
+    ("POST", "/fiscal-retry", Access::Permission(dbt::Permission::HandlePayments)),
+
+    ("POST", "/reservations", Access::Permission(dbt::Permission::ManageReservations)),
+    ("POST", "/reservations-status", Access::Permission(dbt::Permission::ManageReservations)),
+
+    ("POST", "/tables", Access::Permission(dbt::Permission::ManageTables)),
+    ("DELETE", "/tables-{id}", Access::Permission(dbt::Permission::ManageTables)),
+    ("GET", "/tables-{id}/token", Access::Permission(dbt::Permission::ManageTables)),
+    ("POST", "/tables-token-rotate", Access::Permission(dbt::Permission::ManageTables)),
+
+    ("POST", "/offers", Access::Permission(dbt::Permission::ManageMenu)),
+    ("DELETE", "/offers/{id}", Access::Permission(dbt::Permission::ManageMenu)),
+    ("POST", "/categories", Access::Permission(dbt::Permission::ManageMenu)),
+    ("DELETE", "/categories/{id}", Access::Permission(dbt::Permission::ManageMenu)),
+    ("POST", "/promotions", Access::Permission(dbt::Permission::ManageMenu)),
+    ("DELETE", "/promotions/{id}", Access::Permission(dbt::Permission::ManageMenu)),
+    ("POST", "/tax-rates", Access::Permission(dbt::Permission::ManageMenu)),
+    ("DELETE", "/tax-rates/{id}", Access::Permission(dbt::Permission::ManageMenu)),
+    ("POST", "/stations", Access::Permission(dbt::Permission::ManageMenu)),
+    ("DELETE", "/stations/{id}", Access::Permission(dbt::Permission::ManageMenu)),
+
+    ("POST", "/stock", Access::Permission(dbt::Permission::ManageStock)),
+    ("DELETE", "/stock/{id}", Access::Permission(dbt::Permission::ManageStock)),
+    ("POST", "/stock-receive", Access::Permission(dbt::Permission::ManageStock)),
+    ("POST", "/stock-adjust", Access::Permission(dbt::Permission::ManageStock)),
+
+    ("POST", "/venue", Access::Permission(dbt::Permission::ManageSettings)),
+    ("GET", "/fiscal", Access::Permission(dbt::Permission::ManageSettings)),
+    ("POST", "/fiscal", Access::Permission(dbt::Permission::ManageSettings)),
+    ("GET", "/fiscal-pending", Access::Permission(dbt::Permission::ManageSettings)),
+
+    ("GET", "/reports-{date}", Access::Permission(dbt::Permission::ViewReports)),
+
+    ("GET", "/staff", Access::Permission(dbt::Permission::ManageStaff)),
+    ("POST", "/staff", Access::Permission(dbt::Permission::ManageStaff)),
+    ("POST", "/staff-assign", Access::Permission(dbt::Permission::ManageStaff)),
+    ("DELETE", "/staff/{id}", Access::Permission(dbt::Permission::ManageStaff)),
+];
+
+/// [`ROUTES`] with their patterns parsed, the ones without parameters
+/// first so that `/bills-pay` isn't taken for `/bills-{id}`.
+static RESOLVER: LazyLock<Vec<(&str, ResourceDef, Access)>> = LazyLock::new(|| {
+    let mut routes = ROUTES
+        .iter()
+        .map(|(method, pattern, access)| (*method, ResourceDef::new(*pattern), *access))
+        .collect::<Vec<(&str, ResourceDef, Access)>>();
+    routes.sort_by_key(|(_, route, _)| route.pattern().is_some_and(|pattern| pattern.contains('{')));
+    routes
+});
+
+/// Who a request for `path` is open to, worked out from the route it
+/// is for and its method.
+pub fn resolve(method: &Method, path: &str) -> Access {
+    RESOLVER
+        .iter()
+        .find(|(route_method, route, _)| *route_method == method.as_str() && route.is_match(path))
+        .map_or(Access::Admin, |(_, _, access)| *access)
+}
+
+pub fn hash_secret(secret: &str) -> Result<String, String> {
+
+    let salt = SaltString::generate(&mut OsRng);
+
+    Argon2::default()
+        .hash_password(secret.as_bytes(), &salt)
+        .map(|hash| hash.to_string())
+        .map_err(|err| err.to_string())
+
+}
+
+pub fn verify_secret(secret: &str, hash: &str) -> bool {
+    match PasswordHash::new(hash) {
+        Ok(hash) => Argon2::default().verify_password(secret.as_bytes(), &hash).is_ok(),
+        Err(_) => false
+    }
+}
+
+/// Why a PIN or password can't be used, if it can't.
+pub fn check_secret(secret: &str) -> Result<(), String> {
+    if secret.chars().count() < SECRET_MIN_LENGTH {
+        return Err(format!(
+            "PIN or password has to be at least {} characters long.", SECRET_MIN_LENGTH
+        ))
+    }
+    Ok(())
+}
+
+fn generate_token() -> String {
+
+    let mut bytes = [0u8; 32];
+    OsRng.fill_bytes(&mut bytes);
+
+    bytes.iter().map(|byte| format!("{:02x}", byte)).collect()
+
+}
+
+/// Checks the secret of the staff member and hands out a new token.
+///
+/// Failed attempts are counted on the account, which gets locked
+/// for a while after too many of them in a row.
+pub fn login(name: &dbt::StaffID, secret: &str, db: &sled::Db) -> Result<(dbt::StaffToken, dbt::Staff), LoginError> {
+
+    let mut staff = match dbt::Staff::get(
+        dbt::Staff {
+            name: name.clone(),
+            ..Default::default()
+        }.qualified_identifier(),
+        db
+    )? {
+        Some(staff) if !staff.disabled => staff,
+        _ => return Err(LoginError::Invalid)
+    };
+
+    let now = clock::timestamp();
+
+    if let Some(until) = staff.locked_until {
+        if until > now {
+            return Err(LoginError::Locked(until - now))
+        }
+    }
+
+    if !verify_secret(secret, &staff.secret_hash) {
+        staff.failed_logins += 1;
+        if staff.failed_logins >= MAX_FAILED_LOGINS {
+            staff.failed_logins = 0;
+            staff.locked_until = Some(now + LOCKOUT);
+        }
+        staff.insert(db)?;
+        return Err(LoginError::Invalid)
+    }
+
+    if staff.failed_logins > 0 || staff.locked_until.is_some() {
+        staff.failed_logins = 0;
+        staff.locked_until = None;
+        staff.insert(db)?;
+    }
+
+    let token = dbt::StaffToken {
+        token: generate_token(),
+        staff: staff.name.clone(),
+        expires_at: now + TOKEN_LIFETIME
+    };
+
+    token.insert(db)?;
+
+    Ok((token, staff))
+
+}
+
+/// The staff member the token belongs to, `None` if the token is
+/// unknown or expired or the account is disabled.
+pub fn staff_of_token(token: &str, db: &sled::Db) -> Result<Option<dbt::Staff>, String> {
+
+    let token = match dbt::StaffToken::get(
+        dbt::StaffToken {
+            token: token.to_string(),
+            ..Default::default()
+        }.qualified_identifier(),
+        db
+    )? {
+        Some(token) => token,
+        None => return Ok(None)
+    };
+
+    if token.expires_at <= clock::timestamp() {
+        token.remove(db)?;
+        return Ok(None)
+    }
+
+    Ok(
+        dbt::Staff::get(
+            dbt::Staff {
+                name: token.staff,
+                ..Default::default()
+            }.qualified_identifier(),
+            db
+        )?
+        .filter(|staff| !staff.disabled)
+    )
+
+}
+
+/// Logs the staff member out everywhere.
+pub fn revoke_tokens(staff: &dbt::StaffID, db: &sled::Db) -> Result<(), String> {
+
+    for token in dbt::StaffToken::get_all(db)? {
+        if &token.staff == staff || token.expires_at <= clock::timestamp() {
+            token.remove(db)?;
+        }
+    }
+
+    Ok(())
+
+}
+
+/// Whether any enabled manager other than `except` is left.
+pub fn other_manager_exists(except: &dbt::StaffID, db: &sled::Db) -> Result<bool, String> {
+    Ok(
+        dbt::Staff::get_all(db)?
+            .iter()
+            .any(|staff| {
+                &staff.name != except
+                && !staff.disabled
+                && authority(staff.role) == req::Authority::Admin
+            })
+    )
+}
+
+fn bearer_token(req: &ServiceRequest) -> Option<String> {
+    req.headers()
+        .get(actix_web::http::header::AUTHORIZATION)?
+        .to_str()
+        .ok()?
+        .strip_prefix("Bearer ")
+        .map(|token| token.trim().to_string())
+}
+
+/// Middleware that checks every request against [`ROUTES`].
+///
+/// Requests carrying a valid token get their [`Authenticated`] staff
+/// member in the extensions, public routes also work without one.
+/// Answers `401` if a token is needed and missing or invalid, and
+/// `403` if the staff member isn't allowed to use the route.
+pub async fn guard(
+    req: ServiceRequest,
+    next: Next<impl MessageBody + 'static>
+) -> Result<ServiceResponse<BoxBody>, actix_web::Error> {
+
+    // Preflight requests carry no credentials.
+    if req.method() == Method::OPTIONS {
+        return next.call(req).await.map(|res| res.map_into_boxed_body())
+    }
+
+    let access = resolve(req.method(), req.path());
+
+    let token = bearer_token(&req);
+
+    let db = req.app_data::<web::Data<Arc<Mutex<sled::Db>>>>().cloned();
+
+    let staff = match (&token, db) {
+        (Some(token), Some(db)) => {
+            let db_locked = match db.lock() {
+                Ok(lock) => lock,
+                Err(_) => {
+                    log::error!("{}", logf!("Failed to lock database!"));
+                    return Ok(req.into_response(
+                        actix_web::HttpResponse::InternalServerError()
+                            .body("Database is in a deadlock. (DEADLOCK REFERENCE 😳)")
+                    ))
+                }
+            };
+            match staff_of_token(token, &db_locked) {
+                Ok(staff) => staff,
+                Err(err) => {
+                    log::error!("{}: {}", logf!("Failed to read value from database!"), err);
+                    return Ok(req.into_response(
+                        actix_web::HttpResponse::InternalServerError()
+                            .body("Database failed.")
+                    ))
+                }
+            }
+        }
+        _ => None
+    };
+
+    match (staff, token) {
+        (Some(staff), Some(token)) => {
+            if !allows(&staff, access) {
+                log::info!("{}: `{}` {} {}", logf!("Forbidden"), staff.name, req.method(), req.path());
+                return Ok(req.into_response(
+                    actix_web::HttpResponse::Forbidden()
+                        .body("Not allowed for your role.")
+                ))
+            }
+            req.extensions_mut().insert(Authenticated { staff, token });
+        }
+        _ if access != Access::Public => {
+            return Ok(req.into_response(
+                actix_web::HttpResponse::Unauthorized()
+                    .insert_header((actix_web::http::header::WWW_AUTHENTICATE, "Bearer"))
+                    .body("Log in first.")
+            ))
+        }
+        _ => ()
+    }
+
+    next.call(req).await.map(|res| res.map_into_boxed_body())
+
+}
+
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    /// Routes of the HTML server, which doesn't go through [`guard`].
+    const HTML_ROUTES: [&str; 3] = ["/kds", "/admin", "/{id}"];
+
+    fn registered_routes() -> Vec<(String, String)> {
+        regex::Regex::new(r#"#\[(get|post|delete)\("([^"]+)"\)\]"#)
+            .unwrap()
+            .captures_iter(include_str!("requests_database.rs"))
+            .map(|route| (route[1].to_uppercase(), route[2].replace("\\\\", "\\")))
+            .filter(|(_, pattern)| !HTML_ROUTES.contains(&pattern.as_str()))
+            .collect()
+    }
+
+    /// A path the route serves, every parameter set to `1`.
+    fn example_path(pattern: &str) -> String {
+        regex::Regex::new(r"\{[^}]*\}")
+            .unwrap()
+            .replace_all(pattern, "1")
+            .into_owned()
+    }
+
+    #[test]
+    fn every_registered_route_is_listed() {
+
+        let registered = registered_routes();
+
+        for (method, pattern) in registered.iter() {
+            assert!(
+                ROUTES.iter().any(|(m, p, _)| m == method && p == pattern),
+                "{} {} is missing from ROUTES", method, pattern
+            );
+        }
+        for (method, pattern, _) in ROUTES.iter() {
+            assert!(
+                registered.iter().any(|(m, p)| m == method && p == pattern),
+                "{} {} is in ROUTES but has no handler", method, pattern
+            );
+        }
+
+    }
+
+    #[test]
+    fn every_route_resolves_to_its_own_access() {
+        for (method, pattern, access) in ROUTES.iter() {
+            let method = Method::from_bytes(method.as_bytes()).unwrap();
+            assert_eq!(
+                resolve(&method, &example_path(pattern)),
+                *access,
+                "{} {}", method, pattern
+            );
+        }
+    }
+
+    #[test]
+    fn routes_sharing_a_prefix_with_a_parameter_keep_their_access() {
+
+        use dbt::Permission::*;
+
+        let routes = [
+            (Method::POST, "/bills-pay", Access::Permission(HandlePayments)),
+            (Method::POST, "/bills-close", Access::Permission(HandlePayments)),
+            (Method::POST, "/tables-state", Access::Permission(TakeOrders)),
+            (Method::POST, "/tables-merge", Access::Permission(TakeOrders)),
+            (Method::POST, "/tables-token-rotate", Access::Permission(ManageTables)),
+            (Method::POST, "/sessions-move", Access::Permission(TakeOrders)),
+            (Method::POST, "/reservations-status", Access::Permission(ManageReservations)),
+            (Method::GET, "/bills-7", Access::Staff),
+            (Method::GET, "/tables-Stol%201", Access::Staff),
+            (Method::DELETE, "/tables-Stol%201", Access::Permission(ManageTables)),
+            (Method::GET, "/tables-Stol%201/orders", Access::Public),
+        ];
+
+        for (method, path, access) in routes {
+            assert_eq!(resolve(&method, path), access, "{} {}", method, path);
+        }
+
+        let waiter = dbt::Staff {
+            role: dbt::Role::Waiter,
+            ..Default::default()
+        };
+        for path in ["/bills-pay", "/bills-close", "/tables-state", "/tables-merge", "/sessions-move", "/reservations-status"] {
+            assert!(allows(&waiter, resolve(&Method::POST, path)), "POST {}", path);
+        }
+
+    }
+
+    #[test]
+    fn only_admins_manage_admin_accounts() {
+
+        let staff_manager = dbt::Staff {
+            role: dbt::Role::Waiter,
+            permissions: vec![dbt::Permission::ManageStaff],
+            ..Default::default()
+        };
+        let manager = dbt::Staff {
+            role: dbt::Role::Manager,
+            ..Default::default()
+        };
+
+        assert!(may_manage(&staff_manager, dbt::Role::Waiter));
+        assert!(may_manage(&staff_manager, dbt::Role::Kitchen));
+        assert!(!may_manage(&staff_manager, dbt::Role::Manager));
+        assert!(may_manage(&manager, dbt::Role::Manager));
+
+        let with = |role: dbt::Role, permissions: &[dbt::Permission]| dbt::Staff {
+            role,
+            permissions: permissions.to_vec(),
+            ..Default::default()
+        };
+        let cook = with(dbt::Role::Kitchen, &[]);
+        let settings = with(dbt::Role::Kitchen, &[dbt::Permission::ManageSettings]);
+
+        assert!(may_grant(&staff_manager, None, &with(dbt::Role::Waiter, &[dbt::Permission::ManageStaff])));
+        assert!(!may_grant(&staff_manager, None, &settings));
+        assert!(!may_grant(&staff_manager, Some(&staff_manager), &with(dbt::Role::Waiter, &[
+            dbt::Permission::ManageStaff,
+            dbt::Permission::ViewReports
+        ])));
+        assert!(may_grant(&staff_manager, Some(&settings), &settings));
+        assert!(may_grant(&staff_manager, Some(&settings), &cook));
+        assert!(may_grant(&manager, Some(&cook), &settings));
+
+    }
+
+    #[test]
+    fn unknown_routes_are_admin_only() {
+        assert_eq!(resolve(&Method::GET, "/nothing-here"), Access::Admin);
+        assert_eq!(resolve(&Method::PUT, "/menu"), Access::Admin);
+        assert_eq!(resolve(&Method::POST, "/menu"), Access::Admin);
+    }
+
+}
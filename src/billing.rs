@@ -0,0 +1,312 @@
+use std::collections::HashMap;
+
+use crate::clock;
+use crate::db;
+use crate::db::DatabaseElement;
+use crate::session;
+use crate::shared::dbt as dbt;
+use crate::shared::req_resp as req;
+use crate::shared::totals;
+
+/// Why a bill operation failed, so handlers can pick a fitting
+/// response.
+#[derive(Debug)]
+pub enum BillingError {
+    NotFound(String),
+    Invalid(String),
+    Database(String),
+}
+
+impl From<String> for BillingError {
+    fn from(err: String) -> Self {
+        BillingError::Database(err)
+    }
+}
+
+/// The open bill of a table, if there is one.
+pub fn open_bill_of_table(
+    table: &dbt::VirtualTableID,
+    db: &sled::Db
+) -> Result<Option<dbt::Bill>, String> {
+
+    Ok(
+        dbt::Bill {
+            table: table.clone(),
+            closed: false,
+            ..Default::default()
+        }
+        .get_templated(db)?
+        .into_iter()
+        .find(|bill| &bill.table == table)
+    )
+
+}
+
+pub fn payments_of_bill(id: dbt::BillID, db: &sled::Db) -> Result<Vec<dbt::Payment>, String> {
+
+    Ok(
+        dbt::Payment {
+            bill: id,
+            ..Default::default()
+        }
+        .get_templated(db)?
+        .into_iter()
+        .filter(|payment| payment.bill == id)
+        .collect()
+    )
+
+}
+
+pub fn summarize(bill: dbt::Bill, db: &sled::Db) -> Result<req::BillSummary, String> {
+
+    let mut orders = Vec::new();
+    for id in bill.orders.iter() {
+        if let Some(order) = db::order_by_id(id, db)? {
+            orders.push(order);
+        }
+    }
+
+    let payments = payments_of_bill(bill.id, db)?;
+
+    let total: u64 = orders.iter()
+        .map(|order| totals::order_totals(order).total.cents())
+        .sum();
+    let paid: u64 = payments.iter().map(|payment| payment.amount.cents()).sum();
+    let tips: u64 = payments.iter().map(|payment| payment.tip.cents()).sum();
+
+    let taxes = totals::tax_breakdown(&totals::merged_lines(&orders));
+
+    Ok(req::BillSummary {
+        bill,
+        orders: orders.into_iter().map(req::TotaledOrder::from).collect(),
+        payments,
+        taxes,
+        total: dbt::Amount::from_cents(total),
+        paid: dbt::Amount::from_cents(paid),
+        remaining: dbt::Amount::from_cents(total.saturating_sub(paid)),
+        tips: dbt::Amount::from_cents(tips)
+    })
+
+}
+
+/// Splits an amount into `ways` shares that differ by at most a
+/// cent, the first shares take the leftover cents.
+pub fn split_evenly(amount: dbt::Amount, ways: u32) -> Vec<dbt::Amount> {
+
+    if ways == 0 {
+        return vec![]
+    }
+
+    let cents = amount.cents();
+    let share = cents / ways as u64;
+    let leftover = cents % ways as u64;
+
+    (0..ways as u64)
+        .map(|i| dbt::Amount::from_cents(share + if i < leftover {1} else {0}))
+        .collect()
+
+}
+
+/// Opens a bill for every unpaid order of the table, or adds orders
+/// that came in since to the table's already open bill.
+pub fn open_bill(table: &dbt::VirtualTableID, db: &sled::Db) -> Result<dbt::Bill, BillingError> {
+
+    let exists = dbt::VirtualTable {
+        name: table.clone(),
+        ..Default::default()
+    }.exists(db)?;
+
+    if !exists {
+        return Err(BillingError::NotFound("Table does not exist!".to_string()))
+    }
+
+    let unpaid = dbt::Order::get_all(db)?
+        .into_iter()
+        .filter(|order| &order.id.table == table && !order.paid)
+        .map(|order| order.id)
+        .collect::<Vec<dbt::OrderID>>();
+
+    let mut bill = match open_bill_of_table(table, db)? {
+        Some(bill) => bill,
+        None => {
+            if unpaid.is_empty() {
+                return Err(BillingError::Invalid("Table has no unpaid orders.".to_string()))
+            }
+            dbt::Bill {
+                id: db.generate_id().map_err(|err| err.to_string())?,
+                table: table.clone(),
+                opened_at: clock::timestamp(),
+                ..Default::default()
+            }
+        }
+    };
+
+    for id in unpaid {
+        if !bill.orders.contains(&id) {
+            bill.orders.push(id);
+        }
+    }
+
+    bill.insert(db)?;
+
+    Ok(bill)
+
+}
+
+/// What the given items cost, making sure that no part of an order
+/// line gets paid for twice.
+fn items_amount(
+    items: &[dbt::PaidItem],
+    summary: &req::BillSummary
+) -> Result<dbt::Amount, BillingError> {
+
+    let mut paid: HashMap<(&dbt::OrderID, usize), u32> = HashMap::new();
+    for item in summary.payments.iter().flat_map(|payment| payment.items.iter()) {
+        *paid.entry((&item.order, item.line)).or_default() += item.count;
+    }
+
+    let mut cents = 0;
+
+    for item in items {
+
+        let line = summary.orders
+            .iter()
+            .find(|order| order.order.id == item.order)
+            .and_then(|order| order.order.items.get(item.line));
+
+        let line = match line {
+            Some(line) => line,
+            None => return Err(BillingError::Invalid(format!(
+                "Line {} of order {} is not on this bill.",
+                item.line, item.order.count
+            )))
+        };
+
+        let already = paid.entry((&item.order, item.line)).or_default();
+        if item.count == 0 || *already + item.count > line.count {
+            return Err(BillingError::Invalid(format!(
+                "Only {} of `{}` left to pay for.",
+                line.count - *already, line.id
+            )))
+        }
+
+        // Shares of the discounted line total, counted from the start
+        // of the line so that paying all pieces adds up to the total.
+        let total = totals::line_total(line).total.cents();
+        let share = |count: u32| total * count as u64 / line.count as u64;
+        cents += share(*already + item.count) - share(*already);
+
+        *already += item.count;
+
+    }
+
+    Ok(dbt::Amount::from_cents(cents))
+
+}
+
+pub fn pay(
+    data: req::BillsPayRequestData,
+    db: &sled::Db
+) -> Result<(dbt::Payment, req::BillSummary), BillingError> {
+
+    let bill = match db::bill_by_id(data.bill, db)? {
+        Some(bill) => bill,
+        None => return Err(BillingError::NotFound("Bill does not exist!".to_string()))
+    };
+
+    if bill.closed {
+        return Err(BillingError::Invalid("Bill is already closed.".to_string()))
+    }
+
+    let summary = summarize(bill, db)?;
+
+    let amount = if !data.items.is_empty() {
+        items_amount(&data.items, &summary)?
+    } else {
+        data.amount.unwrap_or(summary.remaining)
+    };
+
+    if amount.cents() == 0 && data.tip.cents() == 0 {
+        return Err(BillingError::Invalid("Nothing to pay.".to_string()))
+    }
+
+    if amount.cents() > summary.remaining.cents() {
+        return Err(BillingError::Invalid(format!(
+            "Only {} remains to be paid, pay the rest as a tip.",
+            summary.remaining
+        )))
+    }
+
+    let due = amount.cents() + data.tip.cents();
+    let change = match data.tendered {
+        Some(tendered) if tendered.cents() < due => {
+            return Err(BillingError::Invalid(format!(
+                "{} was handed over but {} is due.",
+                tendered, dbt::Amount::from_cents(due)
+            )))
+        }
+        Some(tendered) => dbt::Amount::from_cents(tendered.cents() - due),
+        None => dbt::Amount::default()
+    };
+
+    let payment = dbt::Payment {
+        id: db.generate_id().map_err(|err| err.to_string())?,
+        bill: summary.bill.id,
+        method: data.method,
+        amount,
+        tip: data.tip,
+        tendered: data.tendered,
+        change,
+        items: data.items,
+        created_at: clock::timestamp()
+    };
+
+    payment.insert(db)?;
+
+    let summary = summarize(summary.bill, db)?;
+
+    Ok((payment, summary))
+
+}
+
+/// Closes a fully paid bill and marks all of its orders as paid, the
+/// table's session ends with it if nothing else is left to pay.
+pub fn close(id: dbt::BillID, db: &sled::Db) -> Result<req::BillSummary, BillingError> {
+
+    let bill = match db::bill_by_id(id, db)? {
+        Some(bill) => bill,
+        None => return Err(BillingError::NotFound("Bill does not exist!".to_string()))
+    };
+
+    if bill.closed {
+        return Err(BillingError::Invalid("Bill is already closed.".to_string()))
+    }
+
+    let summary = summarize(bill.clone(), db)?;
+
+    if summary.remaining.cents() != 0 {
+        return Err(BillingError::Invalid(format!(
+            "{} is still left to pay.",
+            summary.remaining
+        )))
+    }
+
+    for order in summary.orders.iter() {
+        let mut order = order.order.clone();
+        order.paid = true;
+        order.insert(db)?;
+    }
+
+    bill.remove(db)?;
+
+    let mut bill = bill;
+    bill.closed = true;
+    bill.closed_at = Some(clock::timestamp());
+    bill.insert(db)?;
+
+    db::set_table_state(&bill.table, dbt::TableState::NeedsCleaning, db)?;
+    session::close_if_settled(&bill.table, db)?;
+
+    Ok(summarize(bill, db)?)
+
+}
@@ -0,0 +1,66 @@
+use chrono::Datelike;
+use chrono::Timelike;
+
+use crate::shared::dbt as dbt;
+
+/// The current day of the week and time of day in the local time
+/// zone of the server, which is the time zone of the venue.
+pub fn now() -> (dbt::Weekday, dbt::TimeOfDay) {
+
+    let now = chrono::Local::now();
+
+    let day = match now.weekday() {
+        chrono::Weekday::Mon => dbt::Weekday::Monday,
+        chrono::Weekday::Tue => dbt::Weekday::Tuesday,
+        chrono::Weekday::Wed => dbt::Weekday::Wednesday,
+        chrono::Weekday::Thu => dbt::Weekday::Thursday,
+        chrono::Weekday::Fri => dbt::Weekday::Friday,
+        chrono::Weekday::Sat => dbt::Weekday::Saturday,
+        chrono::Weekday::Sun => dbt::Weekday::Sunday,
+    };
+
+    (
+        day,
+        dbt::TimeOfDay {
+            hour: now.hour() as u8,
+            minute: now.minute() as u8
+        }
+    )
+
+}
+
+/// Seconds since the unix epoch.
+pub fn timestamp() -> i64 {
+    chrono::Utc::now().timestamp()
+}
+
+/// The local date a timestamp from [`timestamp`] falls on.
+pub fn local_date(timestamp: i64) -> Option<chrono::NaiveDate> {
+    chrono::DateTime::from_timestamp(timestamp, 0)
+        .map(|utc| utc.with_timezone(&chrono::Local).date_naive())
+}
+
+/// Formats a timestamp from [`timestamp`] in local time the way it is
+/// printed on receipts.
+pub fn local_date_time_string(timestamp: i64) -> String {
+    local_format(timestamp, "%d.%m.%Y. %H:%M")
+}
+
+/// Formats a timestamp from [`timestamp`] in local time with a
+/// [`chrono::format::strftime`] format string.
+pub fn local_format(timestamp: i64, format: &str) -> String {
+    match chrono::DateTime::from_timestamp(timestamp, 0) {
+        Some(utc) => utc
+            .with_timezone(&chrono::Local)
+            .format(format)
+            .to_string(),
+        None => String::new()
+    }
+}
+
+/// The year a timestamp from [`timestamp`] falls in, in local time.
+pub fn local_year(timestamp: i64) -> i32 {
+    local_date(timestamp)
+        .map(|date| date.year())
+        .unwrap_or_default()
+}
@@ -209,6 +209,21 @@ pub trait DatabaseElement:
         }
     }
 
+    /// Like [`DatabaseElement::insert`], but only once the batch is
+    /// applied, together with everything else in it.
+    fn insert_batched(&self, batch: &mut sled::Batch) -> Result<(), String> {
+        let serialized = match bincode::serialize(&self) {
+            Ok(result) => result,
+            Err(err) => return Err(err.to_string())
+        };
+        batch.insert(self.qualified_identifier().as_bytes(), serialized);
+        Ok(())
+    }
+
+    fn remove_batched(&self, batch: &mut sled::Batch) {
+        batch.remove(self.qualified_identifier().as_bytes());
+    }
+
     fn exists(&self, db: &sled::Db) -> Result<bool, String> {
         match db.contains_key(self.qualified_identifier()) {
             Ok(result) => Ok(result),
@@ -322,6 +337,24 @@ pub fn database_element_get_kind(s: &str) -> Option<String> {
 pub const OFFER_NAMESPACE:         &'static str = "offer";
 pub const VIRTUAL_TABLE_NAMESPACE: &'static str = "table";
 pub const ORDER_NAMESPACE:         &'static str = "order";
+pub const BILL_NAMESPACE:          &str = "bill";
+pub const PAYMENT_NAMESPACE:       &str = "payment";
+pub const CATEGORY_NAMESPACE:      &str = "category";
+pub const TAX_RATE_NAMESPACE:      &str = "tax";
+pub const VENUE_NAMESPACE:         &str = "venue";
+pub const RECEIPT_NAMESPACE:       &str = "receipt";
+pub const SEQUENCE_NAMESPACE:      &str = "sequence";
+pub const FISCAL_NAMESPACE:        &str = "fiscal";
+pub const STOCK_NAMESPACE:         &str = "stock";
+pub const PROMOTION_NAMESPACE:     &str = "promotion";
+pub const SESSION_NAMESPACE:       &str = "session";
+pub const RESERVATION_NAMESPACE:   &str = "reservation";
+pub const STAFF_NAMESPACE:         &str = "staff";
+pub const TOKEN_NAMESPACE:         &str = "token";
+pub const SIGNING_NAMESPACE:       &str = "signing";
+pub const STATION_NAMESPACE:       &str = "station";
+pub const TICKET_NAMESPACE:        &str = "ticket";
+pub const ALERT_NAMESPACE:         &str = "alert";
 
 impl DatabaseElement for dbt::Offer {
 
@@ -354,6 +387,270 @@ impl DatabaseElement for dbt::Order {
 
 }
 
+impl DatabaseElement for dbt::Bill {
+
+    fn namespace() -> &'static str {BILL_NAMESPACE}
+    fn status(&self) -> Vec<String> {
+        vec![
+            if self.closed {"closed"} else {"open"}.into()
+        ]
+    }
+    fn main_identifier(&self) -> String {self.id.to_string()}
+    fn secondary_identifiers(&self) -> Vec<String> {vec![self.table.clone()]}
+
+}
+
+impl DatabaseElement for dbt::Session {
+
+    fn namespace() -> &'static str {SESSION_NAMESPACE}
+    fn status(&self) -> Vec<String> {
+        vec![
+            if self.closed {"closed"} else {"open"}.into()
+        ]
+    }
+    fn main_identifier(&self) -> String {self.id.to_string()}
+    fn secondary_identifiers(&self) -> Vec<String> {vec![self.table.clone()]}
+
+}
+
+/// Reservations are kept under their date, so a day's bookings are a
+/// single prefix scan.
+impl DatabaseElement for dbt::Reservation {
+
+    fn namespace() -> &'static str {RESERVATION_NAMESPACE}
+    fn status(&self) -> Vec<String> {vec![]}
+    fn main_identifier(&self) -> String {self.id.to_string()}
+    fn secondary_identifiers(&self) -> Vec<String> {vec![self.date.clone()]}
+
+}
+
+impl DatabaseElement for dbt::Station {
+
+    fn namespace() -> &'static str {STATION_NAMESPACE}
+    fn status(&self) -> Vec<String> {vec![]}
+    fn main_identifier(&self) -> String {self.name.clone()}
+    fn secondary_identifiers(&self) -> Vec<String> {vec![]}
+
+}
+
+/// Tickets still being worked on are kept apart from served ones, so
+/// a station's queue is a single prefix scan.
+impl DatabaseElement for dbt::Ticket {
+
+    fn namespace() -> &'static str {TICKET_NAMESPACE}
+    fn status(&self) -> Vec<String> {
+        vec![
+            if self.status == dbt::TicketStatus::Served {"served"} else {"active"}.into()
+        ]
+    }
+    fn main_identifier(&self) -> String {self.id.to_string()}
+    fn secondary_identifiers(&self) -> Vec<String> {vec![self.station.clone()]}
+
+}
+
+/// Open alerts are kept apart from acknowledged ones, like tickets.
+impl DatabaseElement for dbt::Alert {
+
+    fn namespace() -> &'static str {ALERT_NAMESPACE}
+    fn status(&self) -> Vec<String> {
+        vec![
+            if self.acknowledged_at.is_some() {"acknowledged"} else {"open"}.into()
+        ]
+    }
+    fn main_identifier(&self) -> String {self.id.to_string()}
+    fn secondary_identifiers(&self) -> Vec<String> {vec![self.table.clone()]}
+
+}
+
+impl DatabaseElement for dbt::Payment {
+
+    fn namespace() -> &'static str {PAYMENT_NAMESPACE}
+    fn status(&self) -> Vec<String> {vec![]}
+    fn main_identifier(&self) -> String {self.id.to_string()}
+    fn secondary_identifiers(&self) -> Vec<String> {vec![self.bill.to_string()]}
+
+}
+
+impl DatabaseElement for dbt::Category {
+
+    fn namespace() -> &'static str {CATEGORY_NAMESPACE}
+    fn status(&self) -> Vec<String> {vec![]}
+    fn main_identifier(&self) -> String {self.name.clone()}
+    fn secondary_identifiers(&self) -> Vec<String> {vec![]}
+
+}
+
+impl DatabaseElement for dbt::TaxRate {
+
+    fn namespace() -> &'static str {TAX_RATE_NAMESPACE}
+    fn status(&self) -> Vec<String> {vec![]}
+    fn main_identifier(&self) -> String {self.name.clone()}
+    fn secondary_identifiers(&self) -> Vec<String> {vec![]}
+
+}
+
+impl DatabaseElement for dbt::StockItem {
+
+    fn namespace() -> &'static str {STOCK_NAMESPACE}
+    fn status(&self) -> Vec<String> {vec![]}
+    fn main_identifier(&self) -> String {self.name.clone()}
+    fn secondary_identifiers(&self) -> Vec<String> {vec![]}
+
+}
+
+impl DatabaseElement for dbt::Promotion {
+
+    fn namespace() -> &'static str {PROMOTION_NAMESPACE}
+    fn status(&self) -> Vec<String> {vec![]}
+    fn main_identifier(&self) -> String {self.name.clone()}
+    fn secondary_identifiers(&self) -> Vec<String> {vec![]}
+
+}
+
+impl DatabaseElement for dbt::Staff {
+
+    fn namespace() -> &'static str {STAFF_NAMESPACE}
+    fn status(&self) -> Vec<String> {vec![]}
+    fn main_identifier(&self) -> String {self.name.clone()}
+    fn secondary_identifiers(&self) -> Vec<String> {vec![]}
+
+}
+
+impl DatabaseElement for dbt::StaffToken {
+
+    fn namespace() -> &'static str {TOKEN_NAMESPACE}
+    fn status(&self) -> Vec<String> {vec![]}
+    fn main_identifier(&self) -> String {self.token.clone()}
+    fn secondary_identifiers(&self) -> Vec<String> {vec![]}
+
+}
+
+/// There is only ever one venue, stored under `venue/()/venue`.
+impl DatabaseElement for dbt::Venue {
+
+    fn namespace() -> &'static str {VENUE_NAMESPACE}
+    fn status(&self) -> Vec<String> {vec![]}
+    fn main_identifier(&self) -> String {VENUE_NAMESPACE.to_string()}
+    fn secondary_identifiers(&self) -> Vec<String> {vec![]}
+
+}
+
+/// There is only ever one set of fiscal settings, stored under
+/// `fiscal/()/fiscal`.
+impl DatabaseElement for dbt::FiscalSettings {
+
+    fn namespace() -> &'static str {FISCAL_NAMESPACE}
+    fn status(&self) -> Vec<String> {vec![]}
+    fn main_identifier(&self) -> String {FISCAL_NAMESPACE.to_string()}
+    fn secondary_identifiers(&self) -> Vec<String> {vec![]}
+
+}
+
+/// There is only ever one signing key, stored under
+/// `signing/()/signing`.
+impl DatabaseElement for dbt::SigningKey {
+
+    fn namespace() -> &'static str {SIGNING_NAMESPACE}
+    fn status(&self) -> Vec<String> {vec![]}
+    fn main_identifier(&self) -> String {SIGNING_NAMESPACE.to_string()}
+    fn secondary_identifiers(&self) -> Vec<String> {vec![]}
+
+}
+
+impl DatabaseElement for dbt::Receipt {
+
+    fn namespace() -> &'static str {RECEIPT_NAMESPACE}
+    fn status(&self) -> Vec<String> {vec![]}
+    fn main_identifier(&self) -> String {self.id.number.to_string()}
+    fn secondary_identifiers(&self) -> Vec<String> {vec![self.id.year.to_string()]}
+
+}
+
+/// Sets the state of a table, `false` if there is no such table.
+pub fn set_table_state(
+    name: &dbt::VirtualTableID,
+    state: dbt::TableState,
+    db: &sled::Db
+) -> Result<bool, String> {
+
+    let table = dbt::VirtualTable::get(
+        dbt::VirtualTable {
+            name: name.clone(),
+            ..Default::default()
+        }.qualified_identifier(),
+        db
+    )?;
+
+    match table {
+        Some(mut table) => {
+            table.state = state;
+            table.insert(db)?;
+            Ok(true)
+        }
+        None => Ok(false)
+    }
+
+}
+
+/// Increments the named counter and returns its new value, the first
+/// value of a counter is 1.
+///
+/// Unlike [`sled::Db::generate_id`] the values have no gaps, which
+/// matters for things like receipt numbers.
+pub fn next_sequence(name: &str, db: &sled::Db) -> Result<u32, String> {
+
+    let key = [SEQUENCE_NAMESPACE, name].join("/");
+
+    let value = db.update_and_fetch(key, |old| {
+        let old = old
+            .and_then(|bytes| <[u8; 4]>::try_from(bytes).ok())
+            .map(u32::from_be_bytes)
+            .unwrap_or(0);
+        Some((old + 1).to_be_bytes().to_vec())
+    });
+
+    match value {
+        Ok(Some(bytes)) => match <[u8; 4]>::try_from(bytes.as_ref()) {
+            Ok(bytes) => Ok(u32::from_be_bytes(bytes)),
+            Err(err) => Err(err.to_string())
+        },
+        Ok(None) => Err("Sequence vanished.".to_string()),
+        Err(err) => Err(err.to_string())
+    }
+
+}
+
+/// Looks an order up by its [`OrderID`](dbt::OrderID) alone, without
+/// knowing whether it is finished or not.
+pub fn order_by_id(id: &dbt::OrderID, db: &sled::Db) -> Result<Option<dbt::Order>, String> {
+
+    for finished in [false, true] {
+        let template = dbt::Order {
+            id: id.clone(),
+            finished,
+            ..Default::default()
+        };
+        if let Some(order) = dbt::Order::get(template.qualified_identifier(), db)? {
+            return Ok(Some(order))
+        }
+    }
+
+    Ok(None)
+
+}
+
+/// Looks a bill up by its id alone, without knowing its table or
+/// whether it is closed.
+pub fn bill_by_id(id: dbt::BillID, db: &sled::Db) -> Result<Option<dbt::Bill>, String> {
+
+    Ok(
+        dbt::Bill::get_all(db)?
+            .into_iter()
+            .find(|bill| bill.id == id)
+    )
+
+}
+
 // impl dbt::Order {
 
 //     pub fn partial_qualified_identifier(&self) -> String {
@@ -0,0 +1,309 @@
+//! Fiscalization of receipts with the Croatian tax authority.
+//!
+//! Every receipt gets a ZKI (protective code of the issuer), the MD5
+//! of the receipt's key data signed with the venue's certificate, and
+//! is then sent to the fiscalization service (CIS) which answers with
+//! a JIR (unique receipt identifier). Receipts that couldn't be sent
+//! keep their ZKI and are sent again later, marked as a subsequent
+//! delivery.
+//!
+//! Signing and sending are behind the [`Signer`] and [`Transport`]
+//! traits, for now the only transport is a local mock of the CIS.
+
+use rsa::pkcs1::DecodeRsaPrivateKey;
+use rsa::pkcs8::DecodePrivateKey;
+use sha1::Digest;
+
+use crate::clock;
+use crate::db::DatabaseElement;
+use crate::shared::dbt as dbt;
+
+/// How often pending receipts are sent again, in seconds.
+pub const RETRY_INTERVAL: u64 = 60;
+
+/// Signs data with the private key of the venue's certificate.
+pub trait Signer {
+    fn sign(&self, data: &[u8]) -> Result<Vec<u8>, String>;
+}
+
+/// Signs with RSA-SHA1 (PKCS #1 v1.5), as the ZKI requires.
+pub struct RsaSigner {
+    key: rsa::RsaPrivateKey
+}
+
+impl RsaSigner {
+
+    /// Loads a PEM encoded PKCS #8 or PKCS #1 private key.
+    pub fn from_pem_file(path: &str) -> Result<Self, String> {
+
+        let pem = std::fs::read_to_string(path)
+            .map_err(|err| format!("Failed to read key `{}`: {}", path, err))?;
+
+        let key = rsa::RsaPrivateKey::from_pkcs8_pem(&pem)
+            .or_else(|_| rsa::RsaPrivateKey::from_pkcs1_pem(&pem))
+            .map_err(|err| format!("Failed to parse key `{}`: {}", path, err))?;
+
+        Ok(RsaSigner { key })
+
+    }
+
+}
+
+impl Signer for RsaSigner {
+
+    fn sign(&self, data: &[u8]) -> Result<Vec<u8>, String> {
+        self.key
+            .sign(rsa::Pkcs1v15Sign::new::<sha1::Sha1>(), &sha1::Sha1::digest(data))
+            .map_err(|err| err.to_string())
+    }
+
+}
+
+/// Everything the CIS needs to know about a receipt.
+#[derive(Debug, Clone)]
+pub struct FiscalRequest {
+    pub oib:              String,
+    pub in_vat_system:    bool,
+    /// `dd.MM.yyyyTHH:mm:ss`
+    pub issued_at:        String,
+    /// Receipts are numbered per premises (`P`).
+    pub sequence_mark:    char,
+    pub number:           u32,
+    pub premises:         String,
+    pub device:           String,
+    pub taxes:            Vec<dbt::TaxBreakdown>,
+    pub total:            dbt::Amount,
+    /// `G` cash, `K` card, `O` anything else or mixed.
+    pub payment_method:   char,
+    pub operator_oib:     String,
+    pub zki:              String,
+    /// Set when the receipt is sent after it was already handed out.
+    pub late_delivery:    bool,
+}
+
+/// Sends fiscal requests to the CIS and returns the JIR.
+pub trait Transport {
+    fn send(&self, request: &FiscalRequest) -> Result<String, String>;
+}
+
+/// Answers like the CIS would without leaving the process.
+pub struct MockCis {
+    pub offline: bool
+}
+
+impl Transport for MockCis {
+
+    fn send(&self, request: &FiscalRequest) -> Result<String, String> {
+
+        if self.offline {
+            return Err("CIS is not reachable.".to_string())
+        }
+
+        let is_oib = |oib: &str| oib.len() == 11 && oib.chars().all(|c| c.is_ascii_digit());
+        let gross: u64 = request.taxes.iter().map(|tax| tax.gross.cents()).sum();
+
+        let problem = if !is_oib(&request.oib) || !is_oib(&request.operator_oib) {
+            Some("malformed OIB")
+        } else if request.zki.len() != 32 {
+            Some("malformed ZKI")
+        } else if request.premises.is_empty() || request.device.is_empty() {
+            Some("missing premises or device code")
+        } else if request.sequence_mark != 'P' && request.sequence_mark != 'N' {
+            Some("unknown sequence mark")
+        } else if !"GKO".contains(request.payment_method) {
+            Some("unknown payment method")
+        } else if request.in_vat_system && gross != request.total.cents() {
+            Some("taxes don't add up to the total")
+        } else {
+            None
+        };
+
+        if let Some(problem) = problem {
+            return Err(format!("CIS rejected the request: {}.", problem))
+        }
+
+        log::info!(
+            "Mock CIS accepted receipt {}/{}/{}{}.",
+            request.number, request.premises, request.device,
+            if request.late_delivery {" (subsequent delivery)"} else {""}
+        );
+
+        let hex = hex(&md5::Md5::digest(
+            format!("{}{}{}", request.zki, request.issued_at, request.number).as_bytes()
+        ));
+
+        Ok(format!(
+            "{}-{}-{}-{}-{}",
+            &hex[0..8], &hex[8..12], &hex[12..16], &hex[16..20], &hex[20..32]
+        ))
+
+    }
+
+}
+
+pub fn transport(kind: dbt::FiscalTransport) -> Box<dyn Transport> {
+    match kind {
+        dbt::FiscalTransport::MockCis => Box::new(MockCis { offline: false }),
+        dbt::FiscalTransport::MockCisOffline => Box::new(MockCis { offline: true }),
+    }
+}
+
+fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|byte| format!("{:02x}", byte)).collect()
+}
+
+/// The data the ZKI is computed from, concatenated without
+/// separators.
+pub fn zki_input(receipt: &dbt::Receipt, fiscal: &dbt::FiscalData) -> String {
+    format!(
+        "{}{}{}{}{}{}",
+        receipt.venue.oib,
+        clock::local_format(receipt.issued_at, "%d.%m.%Y %H:%M:%S"),
+        receipt.id.number,
+        fiscal.premises,
+        fiscal.device,
+        receipt.total
+    )
+}
+
+/// MD5 of the signed input, as 32 lowercase hex digits.
+pub fn zki(input: &str, signer: &dyn Signer) -> Result<String, String> {
+    Ok(hex(&md5::Md5::digest(signer.sign(input.as_bytes())?)))
+}
+
+fn payment_method(receipt: &dbt::Receipt) -> char {
+
+    let cash = receipt.payments.iter().any(|payment| payment.method == dbt::PaymentMethod::Cash);
+    let card = receipt.payments.iter().any(|payment| payment.method == dbt::PaymentMethod::Card);
+
+    match (cash, card) {
+        (_, false) => 'G',
+        (false, true) => 'K',
+        (true, true) => 'O',
+    }
+
+}
+
+pub fn request(receipt: &dbt::Receipt, fiscal: &dbt::FiscalData) -> FiscalRequest {
+    FiscalRequest {
+        oib: receipt.venue.oib.clone(),
+        in_vat_system: fiscal.in_vat_system,
+        issued_at: clock::local_format(receipt.issued_at, "%d.%m.%YT%H:%M:%S"),
+        sequence_mark: 'P',
+        number: receipt.id.number,
+        premises: fiscal.premises.clone(),
+        device: fiscal.device.clone(),
+        taxes: receipt.taxes.clone(),
+        total: receipt.total,
+        payment_method: payment_method(receipt),
+        operator_oib: fiscal.operator_oib.clone(),
+        zki: fiscal.zki.clone().unwrap_or_default(),
+        late_delivery: fiscal.attempts > 0
+    }
+}
+
+fn send(
+    receipt: &dbt::Receipt,
+    fiscal: &mut dbt::FiscalData,
+    settings: &dbt::FiscalSettings
+) -> Result<String, String> {
+
+    if fiscal.zki.is_none() {
+        let signer = RsaSigner::from_pem_file(&settings.key_path)?;
+        fiscal.zki = Some(zki(&zki_input(receipt, fiscal), &signer)?);
+    }
+
+    transport(settings.transport).send(&request(receipt, fiscal))
+
+}
+
+/// Computes the ZKI of a receipt if it doesn't have one yet and sends
+/// the receipt to the CIS, the outcome is recorded in the receipt's
+/// [`dbt::FiscalData`] either way.
+pub fn fiscalize(
+    receipt: &mut dbt::Receipt,
+    settings: &dbt::FiscalSettings
+) -> Result<String, String> {
+
+    let mut fiscal = receipt.fiscal.take().unwrap_or_else(|| dbt::FiscalData {
+        premises: settings.premises.clone(),
+        device: settings.device.clone(),
+        operator_oib: settings.operator_oib.clone(),
+        in_vat_system: settings.in_vat_system,
+        ..Default::default()
+    });
+
+    let result = send(receipt, &mut fiscal, settings);
+
+    fiscal.attempts += 1;
+    match &result {
+        Ok(jir) => {
+            fiscal.jir = Some(jir.clone());
+            fiscal.last_error = None;
+        }
+        Err(err) => fiscal.last_error = Some(err.clone())
+    }
+
+    receipt.fiscal = Some(fiscal);
+
+    result
+
+}
+
+pub fn settings(db: &sled::Db) -> Result<dbt::FiscalSettings, String> {
+    Ok(
+        dbt::FiscalSettings::get(dbt::FiscalSettings::default().qualified_identifier(), db)?
+            .unwrap_or_default()
+    )
+}
+
+/// Fiscalizes a freshly issued receipt if fiscalization is enabled.
+/// A failed attempt doesn't stop the receipt from being issued, it
+/// stays pending until [`retry_pending`] gets it through.
+pub fn fiscalize_issued(receipt: &mut dbt::Receipt, db: &sled::Db) -> Result<(), String> {
+
+    let settings = settings(db)?;
+
+    if !settings.enabled {
+        return Ok(())
+    }
+
+    if let Err(err) = fiscalize(receipt, &settings) {
+        log::warn!("Receipt {} is pending fiscalization: {}", receipt.id, err);
+    }
+
+    Ok(())
+
+}
+
+/// Receipts that were fiscalized but have no JIR yet.
+pub fn pending(db: &sled::Db) -> Result<Vec<dbt::Receipt>, String> {
+    Ok(
+        dbt::Receipt::get_all(db)?
+            .into_iter()
+            .filter(|receipt| receipt.fiscal.as_ref().is_some_and(|fiscal| fiscal.jir.is_none()))
+            .collect()
+    )
+}
+
+/// Sends all pending receipts again, returns how many went through
+/// and how many are still pending.
+pub fn retry_pending(db: &sled::Db) -> Result<(usize, usize), String> {
+
+    let settings = settings(db)?;
+    let (mut sent, mut failed) = (0, 0);
+
+    for mut receipt in pending(db)? {
+        match fiscalize(&mut receipt, &settings) {
+            Ok(_) => sent += 1,
+            Err(err) => {
+                log::warn!("Receipt {} is still pending fiscalization: {}", receipt.id, err);
+                failed += 1;
+            }
+        }
+        receipt.insert(db)?;
+    }
+
+    Ok((sent, failed))
+
+}
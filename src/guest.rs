@@ -0,0 +1,102 @@
+use argon2::password_hash::rand_core::{OsRng, RngCore};
+use blake2::digest::{KeyInit, Mac};
+use blake2::Blake2sMac256;
+use percent_encoding::{utf8_percent_encode, NON_ALPHANUMERIC};
+
+use crate::db::DatabaseElement;
+use crate::shared::dbt as dbt;
+use crate::shared::req_resp as req;
+
+/// The signing key, a new one is generated and stored if there is
+/// none yet.
+pub fn signing_key(db: &sled::Db) -> Result<Vec<u8>, String> {
+
+    let template = dbt::SigningKey::default();
+
+    if let Some(signing) = dbt::SigningKey::get(template.qualified_identifier(), db)? {
+        return Ok(signing.key)
+    }
+
+    let mut key = vec![0u8; 32];
+    OsRng.fill_bytes(&mut key);
+
+    dbt::SigningKey { key: key.clone() }.insert(db)?;
+
+    Ok(key)
+
+}
+
+fn mac(key: &[u8], table: &dbt::VirtualTable, generation: u32) -> Blake2sMac256 {
+
+    let mut mac = <Blake2sMac256 as KeyInit>::new_from_slice(key)
+        .expect("Signing key is longer than 32 bytes.");
+    mac.update(table.name.as_bytes());
+    mac.update(&[0]);
+    mac.update(&generation.to_be_bytes());
+
+    mac
+
+}
+
+/// Token authorizing guests to order for the table, in the
+/// `generation.signature` format.
+pub fn table_token(table: &dbt::VirtualTable, key: &[u8]) -> String {
+
+    let signature = mac(key, table, table.token_generation)
+        .finalize()
+        .into_bytes()
+        .iter()
+        .map(|byte| format!("{:02x}", byte))
+        .collect::<String>();
+
+    format!("{}.{}", table.token_generation, signature)
+
+}
+
+/// Whether the token was signed for the table and hasn't been
+/// rotated out since.
+pub fn verify(token: &str, table: &dbt::VirtualTable, key: &[u8]) -> bool {
+
+    let (generation, signature) = match token.split_once('.') {
+        Some(parts) => parts,
+        None => return false
+    };
+
+    if generation.parse::<u32>().ok() != Some(table.token_generation) {
+        return false
+    }
+
+    let signature = match (0..signature.len())
+        .step_by(2)
+        .map(|i| signature.get(i..i + 2).and_then(|byte| u8::from_str_radix(byte, 16).ok()))
+        .collect::<Option<Vec<u8>>>()
+    {
+        Some(signature) => signature,
+        None => return false
+    };
+
+    mac(key, table, table.token_generation)
+        .verify_slice(&signature)
+        .is_ok()
+
+}
+
+/// Link to the guest page of the table, the one printed on its QR
+/// code.
+pub fn guest_url(ip: &str, table: &dbt::VirtualTable, key: &[u8]) -> String {
+    format!(
+        "http://{}:{}/{}?token={}",
+        ip,
+        req::HTML_PORT,
+        utf8_percent_encode(&table.name, NON_ALPHANUMERIC),
+        table_token(table, key)
+    )
+}
+
+pub fn guest_link(ip: &str, table: &dbt::VirtualTable, key: &[u8]) -> req::TablesTokenResponseData {
+    req::TablesTokenResponseData {
+        table: table.name.clone(),
+        token: table_token(table, key),
+        url: guest_url(ip, table, key)
+    }
+}
@@ -0,0 +1,98 @@
+use crate::shared::dbt as dbt;
+use crate::shared::req_resp as req;
+
+/// Language codes asked for, the `lang` parameter first and then the
+/// `Accept-Language` header from the most wanted on.
+pub fn requested(lang: Option<&str>, accept_language: Option<&str>) -> Vec<String> {
+
+    let mut accepted = accept_language
+        .unwrap_or_default()
+        .split(',')
+        .filter_map(|entry| {
+            let mut parts = entry.split(';');
+            let language = parts.next()?.trim();
+            let quality = parts
+                .find_map(|part| part.trim().strip_prefix("q="))
+                .map_or(Some(1.0), |quality| quality.trim().parse::<f32>().ok())?;
+            (!language.is_empty() && language != "*" && quality > 0.0)
+                .then(|| (language.to_lowercase(), quality))
+        })
+        .collect::<Vec<(String, f32)>>();
+
+    // Stable, so languages of the same quality keep their order.
+    accepted.sort_by(|a, b| b.1.total_cmp(&a.1));
+
+    lang.map(|lang| lang.trim().to_lowercase())
+        .filter(|lang| !lang.is_empty())
+        .into_iter()
+        .chain(accepted.into_iter().map(|(language, _)| language))
+        .collect()
+
+}
+
+/// Languages the menu can be shown in, the venue's own first.
+pub fn available(base: &str, offers: &[dbt::Offer], categories: &[dbt::Category]) -> Vec<String> {
+
+    let mut languages = offers
+        .iter()
+        .flat_map(|offer| offer.translations.keys())
+        .chain(categories.iter().flat_map(|category| category.translations.keys()))
+        .map(|language| language.to_lowercase())
+        .filter(|language| language != base)
+        .collect::<Vec<String>>();
+
+    languages.sort();
+    languages.dedup();
+
+    if !base.is_empty() {
+        languages.insert(0, base.to_string());
+    }
+
+    languages
+
+}
+
+/// The first requested language that is available, `en-GB` falls
+/// back to `en`. Without one the venue's own language is used.
+pub fn negotiate(requested: &[String], available: &[String], base: &str) -> String {
+    requested
+        .iter()
+        .flat_map(|language| [
+            language.clone(),
+            language.split('-').next().unwrap_or_default().to_string()
+        ])
+        .find(|language| available.contains(language))
+        .unwrap_or_else(|| base.to_string())
+}
+
+/// The offer in the language, in the venue's own where it has no
+/// translation.
+pub fn localize(offer: dbt::Offer, categories: &[dbt::Category], language: &str) -> req::MenuOffer {
+
+    let localized = offer.translations
+        .iter()
+        .find(|(key, _)| key.eq_ignore_ascii_case(language))
+        .map(|(_, translation)| translation.clone())
+        .unwrap_or_else(|| dbt::OfferTranslation {
+            name: offer.name.clone(),
+            description: offer.description.clone()
+        });
+
+    let category_name = categories
+        .iter()
+        .find(|category| Some(&category.name) == offer.category.as_ref())
+        .map(|category| {
+            category.translations
+                .iter()
+                .find(|(key, _)| key.eq_ignore_ascii_case(language))
+                .map(|(_, name)| name.clone())
+                .unwrap_or_else(|| category.name.clone())
+        });
+
+    req::MenuOffer {
+        offer,
+        localized,
+        category_name
+    }
+
+}
@@ -0,0 +1,139 @@
+use std::collections::HashMap;
+
+use crate::db::DatabaseElement;
+use crate::shared::dbt as dbt;
+use crate::shared::req_resp as req;
+
+/// How much of each stock item an order uses up.
+pub fn consumption(order: &dbt::Order, offers: &[dbt::Offer]) -> HashMap<dbt::StockItemID, i64> {
+
+    let mut used: HashMap<dbt::StockItemID, i64> = HashMap::new();
+
+    for item in order.items.iter() {
+        if let Some(offer) = offers.iter().find(|offer| offer.name == item.id) {
+            for ingredient in offer.recipe.iter() {
+                *used.entry(ingredient.stock.clone()).or_default()
+                    += ingredient.quantity * item.count as i64;
+            }
+        }
+    }
+
+    used
+
+}
+
+/// Lines of the order that need a stock item there isn't enough of
+/// for the whole order. Stock items that don't exist aren't tracked
+/// and never run out.
+pub fn check(
+    order: &dbt::Order,
+    offers: &[dbt::Offer],
+    stock: &[dbt::StockItem]
+) -> Vec<req::OrderLineError> {
+
+    let short = consumption(order, offers)
+        .into_iter()
+        .filter(|(name, used)| {
+            stock.iter().any(|item| &item.name == name && item.quantity < *used)
+        })
+        .map(|(name, _)| name)
+        .collect::<Vec<dbt::StockItemID>>();
+
+    let mut errors = Vec::new();
+
+    for (line, item) in order.items.iter().enumerate() {
+        let recipe = match offers.iter().find(|offer| offer.name == item.id) {
+            Some(offer) => &offer.recipe,
+            None => continue
+        };
+        for ingredient in recipe.iter().filter(|ingredient| short.contains(&ingredient.stock)) {
+            errors.push(req::OrderLineError {
+                line: Some(line),
+                offer: Some(item.id.clone()),
+                problem: req::OrderProblem::OutOfStock { stock: ingredient.stock.clone() }
+            });
+        }
+    }
+
+    errors
+
+}
+
+/// Whether a stock item of the offer's recipe is at or below its
+/// threshold, or there isn't enough of it left for a single piece.
+pub fn is_out_of_stock(offer: &dbt::Offer, stock: &[dbt::StockItem]) -> bool {
+    offer.recipe.iter().any(|ingredient| {
+        stock.iter().any(|item| {
+            item.name == ingredient.stock
+            && (item.quantity <= item.low_threshold || item.quantity < ingredient.quantity)
+        })
+    })
+}
+
+/// Brings the `out_of_stock` flag of every offer in line with the
+/// current stock.
+pub fn refresh_offers(db: &sled::Db) -> Result<(), String> {
+
+    let stock = dbt::StockItem::get_all(db)?;
+
+    for mut offer in dbt::Offer::get_all(db)? {
+        let out_of_stock = is_out_of_stock(&offer, &stock);
+        if offer.out_of_stock != out_of_stock {
+            offer.out_of_stock = out_of_stock;
+            offer.insert(db)?;
+        }
+    }
+
+    Ok(())
+
+}
+
+/// Runs `change` on a stock item and stores it, `None` if there is
+/// no such item.
+pub fn update(
+    name: &dbt::StockItemID,
+    change: impl FnOnce(&mut dbt::StockItem),
+    db: &sled::Db
+) -> Result<Option<dbt::StockItem>, String> {
+
+    let item = dbt::StockItem::get(
+        dbt::StockItem {
+            name: name.clone(),
+            ..Default::default()
+        }.qualified_identifier(),
+        db
+    )?;
+
+    let mut item = match item {
+        Some(item) => item,
+        None => return Ok(None)
+    };
+
+    change(&mut item);
+    item.insert(db)?;
+    refresh_offers(db)?;
+
+    Ok(Some(item))
+
+}
+
+fn apply(order: &dbt::Order, offers: &[dbt::Offer], sign: i64, db: &sled::Db) -> Result<(), String> {
+
+    for (name, used) in consumption(order, offers) {
+        update(&name, |item| item.quantity += sign * used, db)?;
+    }
+
+    Ok(())
+
+}
+
+/// Takes what the order uses out of the stock.
+pub fn consume(order: &dbt::Order, offers: &[dbt::Offer], db: &sled::Db) -> Result<(), String> {
+    apply(order, offers, -1, db)
+}
+
+/// Puts what the order used back into the stock, for orders that
+/// were cancelled before they were made.
+pub fn restock(order: &dbt::Order, offers: &[dbt::Offer], db: &sled::Db) -> Result<(), String> {
+    apply(order, offers, 1, db)
+}
@@ -1,6 +1,24 @@
+mod alerts;
+mod auth;
+mod billing;
+mod clock;
 mod db;
+mod fiscal;
+mod guest;
+mod i18n;
+mod inventory;
+mod migrate;
+mod promotions;
+mod receipt;
+mod reports;
+mod reservations;
+mod sections;
 mod shared;
 mod requests_database;
+mod session;
+mod tickets;
+mod transfer;
+mod validation;
 
 use std::{net::TcpStream, path::PathBuf, sync::{Arc, Mutex}, task};
 
@@ -35,6 +53,86 @@ pub fn print_db(db: &sled::Db) {
                             .expect(format!("Failed to convert a suspected `{}` into its type.", db::VIRTUAL_TABLE_NAMESPACE ).as_str());
                         eprintln!("{:#?}\n", value);
                     }
+                    db::BILL_NAMESPACE => {
+                        let value = bincode::deserialize::<dbt::Bill>(&value_own)
+                            .unwrap_or_else(|_| panic!("Failed to convert a suspected `{}` into its type.", db::BILL_NAMESPACE));
+                        eprintln!("{:#?}\n", value);
+                    }
+                    db::CATEGORY_NAMESPACE => {
+                        let value = bincode::deserialize::<dbt::Category>(&value_own)
+                            .unwrap_or_else(|_| panic!("Failed to convert a suspected `{}` into its type.", db::CATEGORY_NAMESPACE));
+                        eprintln!("{:#?}\n", value);
+                    }
+                    db::TAX_RATE_NAMESPACE => {
+                        let value = bincode::deserialize::<dbt::TaxRate>(&value_own)
+                            .unwrap_or_else(|_| panic!("Failed to convert a suspected `{}` into its type.", db::TAX_RATE_NAMESPACE));
+                        eprintln!("{:#?}\n", value);
+                    }
+                    db::VENUE_NAMESPACE => {
+                        let value = bincode::deserialize::<dbt::Venue>(&value_own)
+                            .unwrap_or_else(|_| panic!("Failed to convert a suspected `{}` into its type.", db::VENUE_NAMESPACE));
+                        eprintln!("{:#?}\n", value);
+                    }
+                    db::RECEIPT_NAMESPACE => {
+                        let value = bincode::deserialize::<dbt::Receipt>(&value_own)
+                            .unwrap_or_else(|_| panic!("Failed to convert a suspected `{}` into its type.", db::RECEIPT_NAMESPACE));
+                        eprintln!("{:#?}\n", value);
+                    }
+                    db::FISCAL_NAMESPACE => {
+                        let value = bincode::deserialize::<dbt::FiscalSettings>(&value_own)
+                            .unwrap_or_else(|_| panic!("Failed to convert a suspected `{}` into its type.", db::FISCAL_NAMESPACE));
+                        eprintln!("{:#?}\n", value);
+                    }
+                    db::STOCK_NAMESPACE => {
+                        let value = bincode::deserialize::<dbt::StockItem>(&value_own)
+                            .unwrap_or_else(|_| panic!("Failed to convert a suspected `{}` into its type.", db::STOCK_NAMESPACE));
+                        eprintln!("{:#?}\n", value);
+                    }
+                    db::PROMOTION_NAMESPACE => {
+                        let value = bincode::deserialize::<dbt::Promotion>(&value_own)
+                            .unwrap_or_else(|_| panic!("Failed to convert a suspected `{}` into its type.", db::PROMOTION_NAMESPACE));
+                        eprintln!("{:#?}\n", value);
+                    }
+                    db::SESSION_NAMESPACE => {
+                        let value = bincode::deserialize::<dbt::Session>(&value_own)
+                            .unwrap_or_else(|_| panic!("Failed to convert a suspected `{}` into its type.", db::SESSION_NAMESPACE));
+                        eprintln!("{:#?}\n", value);
+                    }
+                    db::RESERVATION_NAMESPACE => {
+                        let value = bincode::deserialize::<dbt::Reservation>(&value_own)
+                            .unwrap_or_else(|_| panic!("Failed to convert a suspected `{}` into its type.", db::RESERVATION_NAMESPACE));
+                        eprintln!("{:#?}\n", value);
+                    }
+                    db::STAFF_NAMESPACE => {
+                        let value = bincode::deserialize::<dbt::Staff>(&value_own)
+                            .unwrap_or_else(|_| panic!("Failed to convert a suspected `{}` into its type.", db::STAFF_NAMESPACE));
+                        eprintln!("{:#?}\n", value);
+                    }
+                    db::TOKEN_NAMESPACE => {
+                        let value = bincode::deserialize::<dbt::StaffToken>(&value_own)
+                            .unwrap_or_else(|_| panic!("Failed to convert a suspected `{}` into its type.", db::TOKEN_NAMESPACE));
+                        eprintln!("{:#?}\n", value);
+                    }
+                    db::STATION_NAMESPACE => {
+                        let value = bincode::deserialize::<dbt::Station>(&value_own)
+                            .unwrap_or_else(|_| panic!("Failed to convert a suspected `{}` into its type.", db::STATION_NAMESPACE));
+                        eprintln!("{:#?}\n", value);
+                    }
+                    db::TICKET_NAMESPACE => {
+                        let value = bincode::deserialize::<dbt::Ticket>(&value_own)
+                            .unwrap_or_else(|_| panic!("Failed to convert a suspected `{}` into its type.", db::TICKET_NAMESPACE));
+                        eprintln!("{:#?}\n", value);
+                    }
+                    db::ALERT_NAMESPACE => {
+                        let value = bincode::deserialize::<dbt::Alert>(&value_own)
+                            .unwrap_or_else(|_| panic!("Failed to convert a suspected `{}` into its type.", db::ALERT_NAMESPACE));
+                        eprintln!("{:#?}\n", value);
+                    }
+                    db::PAYMENT_NAMESPACE => {
+                        let value = bincode::deserialize::<dbt::Payment>(&value_own)
+                            .unwrap_or_else(|_| panic!("Failed to convert a suspected `{}` into its type.", db::PAYMENT_NAMESPACE));
+                        eprintln!("{:#?}\n", value);
+                    }
                     _ => {eprintln!("Unknown key kind?")}
                 }
             }
@@ -61,20 +159,163 @@ fn summon_db() -> Arc<Mutex<sled::Db>> {
 
 fn fill_db(db: &sled::Db) {
 
-    vec![
+    let venue = dbt::Venue {
+        name: "OBY Caffe Bar".to_string(),
+        address: "Ulica kralja Tomislava 1, 21000 Split".to_string(),
+        oib: "12345678903".to_string(),
+        footer: Some("Hvala na posjeti!".to_string()),
+        language: "hr".to_string()
+    };
+
+    if !venue.exists(db).unwrap_or(false) {
+        venue
+        .insert(db)
+        .unwrap_or_else(|_| panic!(
+            "Failed to insert element of type `{}` and id `{}`",
+            venue.self_namespace(),
+            venue.main_identifier()
+        ));
+    }
+
+    // Without any account nobody could log in to create one.
+    if dbt::Staff::get_all(db).map(|staff| staff.is_empty()).unwrap_or(false) {
+        let admin = dbt::Staff {
+            name: "admin".to_string(),
+            role: dbt::Role::Manager,
+            secret_hash: auth::hash_secret("0000").expect("Failed to hash the default PIN."),
+            ..Default::default()
+        };
+        admin
+        .insert(db)
+        .unwrap_or_else(|_| panic!(
+            "Failed to insert element of type `{}` and id `{}`",
+            admin.self_namespace(),
+            admin.main_identifier()
+        ));
+        log::warn!("Created the `admin` account with the PIN `0000`, change it!");
+    }
+
+    let tax_rates = vec![
+        dbt::TaxRate {
+            name: "PDV 25%".to_string(),
+            basis_points: 2500
+        },
+        dbt::TaxRate {
+            name: "PDV 13%".to_string(),
+            basis_points: 1300
+        }
+    ];
+
+    tax_rates.iter().for_each(|x|{
+        x
+        .insert(db)
+        .unwrap_or_else(|_| panic!(
+            "Failed to insert element of type `{}` and id `{}`",
+            x.self_namespace(),
+            x.main_identifier()
+        ));
+    });
+
+    ["Šank", "Kuhinja"].iter().for_each(|name| {
+        let station = dbt::Station {
+            name: name.to_string()
+        };
+        station
+        .insert(db)
+        .unwrap_or_else(|_| panic!(
+            "Failed to insert element of type `{}` and id `{}`",
+            station.self_namespace(),
+            station.main_identifier()
+        ));
+    });
+
+    let categories = vec![
+        dbt::Category {
+            name: "Pića".to_string(),
+            tax_rate: Some("PDV 25%".to_string()),
+            station: Some("Šank".to_string()),
+            translations: [
+                ("en".to_string(), "Drinks".to_string()),
+                ("de".to_string(), "Getränke".to_string())
+            ].into()
+        }
+    ];
+
+    categories.iter().for_each(|x|{
+        x
+        .insert(db)
+        .unwrap_or_else(|_| panic!(
+            "Failed to insert element of type `{}` and id `{}`",
+            x.self_namespace(),
+            x.main_identifier()
+        ));
+    });
+
+    let stock = dbt::StockItem {
+        name: "Kava u zrnu".to_string(),
+        unit: "g".to_string(),
+        quantity: 1000,
+        low_threshold: 70
+    };
+
+    if !stock.exists(db).unwrap_or(false) {
+        stock
+        .insert(db)
+        .unwrap_or_else(|_| panic!(
+            "Failed to insert element of type `{}` and id `{}`",
+            stock.self_namespace(),
+            stock.main_identifier()
+        ));
+    }
+
+    let offers = vec![
         dbt::Offer {
             name: "Kava".to_string(),
             description: "Mala kava od sviježi sjemenki.".to_string(),
             price_integer: 1,
-            price_fraction: 50
+            price_fraction: 50,
+            category: Some("Pića".to_string()),
+            tax_rate: Some("PDV 13%".to_string()),
+            recipe: vec![dbt::RecipeItem {
+                stock: "Kava u zrnu".to_string(),
+                quantity: 7
+            }],
+            allergens: vec![dbt::Allergen::Milk],
+            dietary: vec![dbt::DietaryTag::Vegetarian, dbt::DietaryTag::GlutenFree],
+            translations: [
+                ("en".to_string(), dbt::OfferTranslation {
+                    name: "Coffee".to_string(),
+                    description: "Macchiato made from freshly ground beans.".to_string()
+                }),
+                ("de".to_string(), dbt::OfferTranslation {
+                    name: "Kaffee".to_string(),
+                    description: "Macchiato aus frisch gemahlenen Bohnen.".to_string()
+                })
+            ].into(),
+            ..Default::default()
         },
         dbt::Offer {
             name: "Cedevita".to_string(),
             description: "Osvježavajuće piće.".to_string(),
             price_integer: 2,
-            price_fraction: 40
+            price_fraction: 40,
+            category: Some("Pića".to_string()),
+            dietary: vec![
+                dbt::DietaryTag::Vegan,
+                dbt::DietaryTag::GlutenFree,
+                dbt::DietaryTag::LactoseFree
+            ],
+            translations: [
+                ("en".to_string(), dbt::OfferTranslation {
+                    name: "Cedevita".to_string(),
+                    description: "Refreshing vitamin drink.".to_string()
+                })
+            ].into(),
+            ..Default::default()
         }
-    ].into_iter().for_each(|x|{
+    ];
+
+    offers.iter().for_each(|x|{
         x
         .insert(db)
         .expect(
@@ -86,26 +327,87 @@ fn fill_db(db: &sled::Db) {
             );
     });
 
+    inventory::refresh_offers(db).expect("Failed to refresh offers from the stock.");
+
+    [
+        dbt::Promotion {
+            name: "Happy hour".to_string(),
+            scope: dbt::PromotionScope::Category {
+                category: "Pića".to_string()
+            },
+            rule: dbt::PromotionRule::Percentage { basis_points: 2000 },
+            schedule: vec![dbt::AvailabilityWindow {
+                days: vec![
+                    dbt::Weekday::Monday,
+                    dbt::Weekday::Tuesday,
+                    dbt::Weekday::Wednesday,
+                    dbt::Weekday::Thursday,
+                    dbt::Weekday::Friday,
+                ],
+                start: dbt::TimeOfDay { hour: 16, minute: 0 },
+                end: dbt::TimeOfDay { hour: 18, minute: 0 }
+            }],
+            code: None
+        },
+        dbt::Promotion {
+            name: "Osoblje".to_string(),
+            scope: dbt::PromotionScope::Order,
+            rule: dbt::PromotionRule::Percentage { basis_points: 3000 },
+            schedule: vec![],
+            code: Some("OSOBLJE".to_string())
+        }
+    ]
+    .iter()
+    .for_each(|x| {
+        x
+        .insert(db)
+        .unwrap_or_else(|_| panic!(
+            "Failed to insert element of type `{}` and id `{}`",
+            x.self_namespace(),
+            x.main_identifier()
+        ));
+    });
+
     vec![
         dbt::VirtualTable {
             name: "Stol 1".to_string(),
             order_count: 0,
+            zone: "Terasa".to_string(),
+            capacity: 4,
+            position: 1,
+            ..Default::default()
         },
         dbt::VirtualTable {
             name: "Stol 2".to_string(),
             order_count: 3,
+            zone: "Terasa".to_string(),
+            capacity: 4,
+            position: 2,
+            ..Default::default()
         },
         dbt::VirtualTable {
             name: "Stol 3".to_string(),
             order_count: 2,
+            zone: "Unutra".to_string(),
+            capacity: 6,
+            position: 3,
+            ..Default::default()
         },
         dbt::VirtualTable {
             name: "Stol 4".to_string(),
             order_count: 5,
+            zone: "Unutra".to_string(),
+            capacity: 2,
+            position: 4,
+            ..Default::default()
         },
         dbt::VirtualTable {
             name: "Stol 5".to_string(),
             order_count: 7,
+            zone: "Šank".to_string(),
+            capacity: 2,
+            position: 5,
+            ..Default::default()
         }
     ].into_iter().for_each(|x|{
         x
@@ -127,9 +429,10 @@ fn fill_db(db: &sled::Db) {
             },
             finished: false,
             items: vec![
-                dbt::OrderItem {id: "Kava".to_string(), count: 2},
-                dbt::OrderItem {id: "Cedevita".to_string(), count: 4}
-            ]
+                dbt::OrderItem {id: "Kava".to_string(), count: 2, note: None, ..Default::default()},
+                dbt::OrderItem {id: "Cedevita".to_string(), count: 4, note: None, ..Default::default()}
+            ],
+            ..Default::default()
         },
         dbt::Order {
             id: dbt::OrderID {
@@ -138,8 +441,9 @@ fn fill_db(db: &sled::Db) {
             },
             finished: false,
             items: vec![
-                dbt::OrderItem {id: "Kava".to_string(), count: 1},
-            ]
+                dbt::OrderItem {id: "Kava".to_string(), count: 1, note: None, ..Default::default()},
+            ],
+            ..Default::default()
         },
         dbt::Order {
             id: dbt::OrderID {
@@ -148,9 +452,10 @@ fn fill_db(db: &sled::Db) {
             },
             finished: false,
             items: vec![
-                dbt::OrderItem {id: "Kava".to_string(), count: 3},
-                dbt::OrderItem {id: "Cedevita".to_string(), count: 2}
-            ]
+                dbt::OrderItem {id: "Kava".to_string(), count: 3, note: None, ..Default::default()},
+                dbt::OrderItem {id: "Cedevita".to_string(), count: 2, note: None, ..Default::default()}
+            ],
+            ..Default::default()
         },
         dbt::Order {
             id: dbt::OrderID {
@@ -159,11 +464,15 @@ fn fill_db(db: &sled::Db) {
             },
             finished: false,
             items: vec![
-                dbt::OrderItem {id: "Kava".to_string(), count: 5},
-                dbt::OrderItem {id: "Cedevita".to_string(), count: 4}
-            ]
+                dbt::OrderItem {id: "Kava".to_string(), count: 5, note: None, ..Default::default()},
+                dbt::OrderItem {id: "Cedevita".to_string(), count: 4, note: None, ..Default::default()}
+            ],
+            ..Default::default()
         }
-    ].into_iter().for_each(|x|{
+    ].into_iter().map(|mut x|{
+        validation::snapshot_offers(&mut x, &offers, &categories, &tax_rates);
+        x
+    }).for_each(|x|{
         x
         .insert(db)
         .expect(
@@ -195,6 +504,7 @@ async fn main() -> std::io::Result<()> {
     
     {
         let db_safe = db.lock().expect("Cannot lock database.");
+        migrate::run(&db_safe).expect("Failed to migrate database: ");
         fill_db(&*db_safe);
     }
 
@@ -207,26 +517,104 @@ async fn main() -> std::io::Result<()> {
     let db_server = HttpServer::new(move || 
         {
             App::new()
+                .wrap(actix_web::middleware::from_fn(auth::guard))
                 .wrap(actix_web::middleware::Logger::default())
                 .wrap(actix_web::middleware::Logger::new("%a %r"))
                 .wrap(Cors::permissive())
                 .app_data(db_data_db.clone())
 
+                .service(requests_database::handler_login)
+                .service(requests_database::handler_logout)
+                .service(requests_database::handler_me)
+                .service(requests_database::handler_staff)
+                .service(requests_database::handler_staff_insert)
+                .service(requests_database::handler_staff_assign)
+                .service(requests_database::handler_my_tables)
+                .service(requests_database::handler_my_orders)
+                .service(requests_database::handler_staff_delete)
+
                 .service(requests_database::handler_tables)
                 .service(requests_database::handler_tables_specific)
                 .service(requests_database::handler_tables_insert)
                 .service(requests_database::handler_tables_delete)
+                .service(requests_database::handler_tables_bill)
+                .service(requests_database::handler_tables_state)
+                .service(requests_database::handler_tables_session)
+                .service(requests_database::handler_tables_orders)
+                .service(requests_database::handler_alerts)
+                .service(requests_database::handler_alerts_insert)
+                .service(requests_database::handler_alerts_acknowledge)
+                .service(requests_database::handler_tables_merge)
+                .service(requests_database::handler_tables_token)
+                .service(requests_database::handler_tables_token_rotate)
 
                 .service(requests_database::handler_offers)
                 .service(requests_database::handler_offers_specific)
                 .service(requests_database::handler_offers_insert)
                 .service(requests_database::handler_offers_delete)
+                .service(requests_database::handler_offers_sold_out)
+                .service(requests_database::handler_menu)
+
+                .service(requests_database::handler_stations)
+                .service(requests_database::handler_stations_insert)
+                .service(requests_database::handler_stations_delete)
+                .service(requests_database::handler_stations_tickets)
+                .service(requests_database::handler_tickets_bump)
+
+                .service(requests_database::handler_categories)
+                .service(requests_database::handler_categories_insert)
+                .service(requests_database::handler_categories_delete)
+
+                .service(requests_database::handler_promotions)
+                .service(requests_database::handler_promotions_insert)
+                .service(requests_database::handler_promotions_delete)
+
+                .service(requests_database::handler_stock)
+                .service(requests_database::handler_stock_insert)
+                .service(requests_database::handler_stock_delete)
+                .service(requests_database::handler_stock_receive)
+                .service(requests_database::handler_stock_adjust)
+
+                .service(requests_database::handler_tax_rates)
+                .service(requests_database::handler_tax_rates_insert)
+                .service(requests_database::handler_tax_rates_delete)
 
                 .service(requests_database::handler_orders)
                 .service(requests_database::handler_orders_specific)
                 .service(requests_database::handler_orders_insert)
                 .service(requests_database::handler_orders_delete)
+                .service(requests_database::handler_orders_accept)
                 .service(requests_database::handler_orders_finish)
+                .service(requests_database::handler_orders_move)
+
+                .service(requests_database::handler_reservations)
+                .service(requests_database::handler_reservations_insert)
+                .service(requests_database::handler_reservations_status)
+
+                .service(requests_database::handler_sessions)
+                .service(requests_database::handler_sessions_specific)
+                .service(requests_database::handler_sessions_move)
+
+                .service(requests_database::handler_bills)
+                .service(requests_database::handler_bills_specific)
+                .service(requests_database::handler_bills_open)
+                .service(requests_database::handler_bills_pay)
+                .service(requests_database::handler_bills_split)
+                .service(requests_database::handler_bills_close)
+
+                .service(requests_database::handler_receipts_issue)
+                .service(requests_database::handler_receipts_specific)
+                .service(requests_database::handler_receipts_render)
+
+                .service(requests_database::handler_venue)
+                .service(requests_database::handler_venue_insert)
+
+                .service(requests_database::handler_fiscal)
+                .service(requests_database::handler_fiscal_insert)
+                .service(requests_database::handler_fiscal_pending)
+                .service(requests_database::handler_fiscal_retry)
+
+                .service(requests_database::handler_reports_daily)
 
                 .service(requests_database::handler_offers_tables)
 
@@ -242,6 +630,8 @@ async fn main() -> std::io::Result<()> {
                 .wrap(actix_web::middleware::Logger::new("%a %r"))
                 .app_data(db_data_html.clone())
                 .wrap(Cors::permissive())
+                .service(requests_database::handler_kds)
+                .service(requests_database::handler_admin)
                 .service(requests_database::handler_server)
 
         }
@@ -249,6 +639,30 @@ async fn main() -> std::io::Result<()> {
     .bind((IP.clone(), req::HTML_PORT))?
     .run();
 
+    let db_fiscal = db.clone();
+    tokio::task::spawn(async move {
+        let mut interval = tokio::time::interval(
+            std::time::Duration::from_secs(fiscal::RETRY_INTERVAL)
+        );
+        loop {
+            interval.tick().await;
+            let db_locked = match db_fiscal.lock() {
+                Ok(lock) => lock,
+                Err(_) => {
+                    log::error!("Failed to lock database for fiscal retries!");
+                    continue
+                }
+            };
+            match fiscal::retry_pending(&db_locked) {
+                Ok((0, 0)) => (),
+                Ok((sent, pending)) => log::info!(
+                    "Fiscal retry: {} receipts sent, {} still pending.", sent, pending
+                ),
+                Err(err) => log::error!("Fiscal retry failed: {}", err)
+            }
+        }
+    });
+
     let server1_task = tokio::task::spawn(db_server);
     let server2_task = tokio::task::spawn(html_server);
 
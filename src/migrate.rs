@@ -0,0 +1,289 @@
+use serde::{de::DeserializeOwned, Serialize};
+
+use crate::db;
+use crate::db::DatabaseElement;
+use crate::shared::dbt as dbt;
+use crate::validation;
+
+/// Layout version of the stored records. Bump it whenever a stored
+/// type changes and add a step to [`run`] that brings the records of
+/// the older layout up to date.
+pub const SCHEMA_VERSION: u32 = 1;
+
+const SCHEMA_KEY: &str = "schema";
+
+/// Layout of the records written before versioning, tables, orders
+/// and offers were all there was.
+mod v0 {
+
+    use serde::{Deserialize, Serialize};
+
+    use crate::shared::dbt as dbt;
+
+    #[derive(Serialize, Deserialize)]
+    pub struct VirtualTable {
+        pub name: dbt::VirtualTableID,
+        pub order_count: u32,
+    }
+
+    #[derive(Serialize, Deserialize)]
+    pub struct OrderItem {
+        pub id: dbt::OfferID,
+        pub count: u32,
+    }
+
+    #[derive(Serialize, Deserialize)]
+    pub struct Order {
+        pub id: dbt::OrderID,
+        pub finished: bool,
+        pub items: Vec<OrderItem>
+    }
+
+    #[derive(Serialize, Deserialize)]
+    pub struct Offer {
+        pub name:           dbt::OfferID,
+        pub description:    String,
+        pub price_integer:  u32,
+        pub price_fraction: u32,
+    }
+
+}
+
+fn stored_version(db: &sled::Db) -> Result<Option<u32>, String> {
+    match db.get(SCHEMA_KEY) {
+        Ok(Some(bytes)) => match <[u8; 4]>::try_from(bytes.as_ref()) {
+            Ok(bytes) => Ok(Some(u32::from_be_bytes(bytes))),
+            Err(err) => Err(err.to_string())
+        },
+        Ok(None) => Ok(None),
+        Err(err) => Err(err.to_string())
+    }
+}
+
+/// Rewrites every record of the namespace that only decodes in the
+/// old layout, records already in the current one are left alone.
+fn upgrade<Old, New>(
+    namespace: &str,
+    convert: impl Fn(Old) -> New,
+    db: &sled::Db
+) -> Result<usize, String>
+where
+    Old: DeserializeOwned,
+    New: DeserializeOwned + Serialize
+{
+
+    let mut upgraded = 0;
+
+    for entry in db.scan_prefix(format!("{}/", namespace)) {
+        let (key, value) = entry.map_err(|err| err.to_string())?;
+        if bincode::deserialize::<New>(&value).is_ok() {
+            continue
+        }
+        let old = bincode::deserialize::<Old>(&value).map_err(|err| format!(
+            "`{}` is in neither the old nor the current layout: {}",
+            String::from_utf8_lossy(&key), err
+        ))?;
+        let new = bincode::serialize(&convert(old)).map_err(|err| err.to_string())?;
+        db.insert(key, new).map_err(|err| err.to_string())?;
+        upgraded += 1;
+    }
+
+    Ok(upgraded)
+
+}
+
+fn from_v0(db: &sled::Db) -> Result<usize, String> {
+
+    let mut upgraded = upgrade(
+        db::VIRTUAL_TABLE_NAMESPACE,
+        |table: v0::VirtualTable| dbt::VirtualTable {
+            name: table.name,
+            order_count: table.order_count,
+            ..Default::default()
+        },
+        db
+    )?;
+
+    upgraded += upgrade(
+        db::OFFER_NAMESPACE,
+        |offer: v0::Offer| dbt::Offer {
+            name: offer.name,
+            description: offer.description,
+            price_integer: offer.price_integer,
+            price_fraction: offer.price_fraction,
+            ..Default::default()
+        },
+        db
+    )?;
+
+    // Old orders have no snapshot of what was ordered, they get the
+    // offers as they are now.
+    let offers = dbt::Offer::get_all(db)?;
+    let categories = dbt::Category::get_all(db)?;
+    let tax_rates = dbt::TaxRate::get_all(db)?;
+
+    upgraded += upgrade(
+        db::ORDER_NAMESPACE,
+        |order: v0::Order| {
+            let mut order = dbt::Order {
+                id: order.id,
+                finished: order.finished,
+                items: order.items
+                    .into_iter()
+                    .map(|item| dbt::OrderItem {
+                        name: item.id.clone(),
+                        id: item.id,
+                        count: item.count,
+                        ..Default::default()
+                    })
+                    .collect(),
+                ..Default::default()
+            };
+            validation::snapshot_offers(&mut order, &offers, &categories, &tax_rates);
+            order
+        },
+        db
+    )?;
+
+    Ok(upgraded)
+
+}
+
+/// Brings the records of the database up to [`SCHEMA_VERSION`]. A
+/// database without a version is from before versioning, unless it
+/// is empty.
+pub fn run(db: &sled::Db) -> Result<(), String> {
+
+    let version = match stored_version(db)? {
+        Some(version) => version,
+        None if db.is_empty() => SCHEMA_VERSION,
+        None => 0
+    };
+
+    if version > SCHEMA_VERSION {
+        return Err(format!(
+            "Database is at schema version {}, this server only knows up to {}.",
+            version, SCHEMA_VERSION
+        ))
+    }
+
+    if version < 1 {
+        let upgraded = from_v0(db)?;
+        log::info!("Upgraded {} records from schema version 0.", upgraded);
+    }
+
+    db.insert(SCHEMA_KEY, &SCHEMA_VERSION.to_be_bytes())
+        .map_err(|err| err.to_string())?;
+
+    Ok(())
+
+}
+
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    fn temporary_db() -> sled::Db {
+        sled::Config::new()
+            .temporary(true)
+            .open()
+            .expect("Failed to open a temporary database.")
+    }
+
+    /// Stores the old value under the key the current one would have.
+    fn insert_old<T: Serialize>(current: impl DatabaseElement, old: &T, db: &sled::Db) {
+        db.insert(current.qualified_identifier(), bincode::serialize(old).unwrap()).unwrap();
+    }
+
+    #[test]
+    fn records_of_the_old_layout_are_upgraded() {
+
+        let db = temporary_db();
+
+        insert_old(dbt::VirtualTable {
+            name: "1".to_string(),
+            ..Default::default()
+        }, &v0::VirtualTable {
+            name: "1".to_string(),
+            order_count: 7
+        }, &db);
+        insert_old(dbt::Offer {
+            name: "Kava".to_string(),
+            ..Default::default()
+        }, &v0::Offer {
+            name: "Kava".to_string(),
+            description: "Espresso".to_string(),
+            price_integer: 1,
+            price_fraction: 50
+        }, &db);
+        let id = dbt::OrderID {
+            table: "1".to_string(),
+            count: 7
+        };
+        insert_old(dbt::Order {
+            id: id.clone(),
+            ..Default::default()
+        }, &v0::Order {
+            id,
+            finished: false,
+            items: vec![v0::OrderItem {
+                id: "Kava".to_string(),
+                count: 2
+            }]
+        }, &db);
+
+        run(&db).unwrap();
+
+        let tables = dbt::VirtualTable::get_all(&db).unwrap();
+        assert_eq!(tables.len(), 1);
+        assert_eq!(tables[0].order_count, 7);
+
+        let offers = dbt::Offer::get_all(&db).unwrap();
+        assert_eq!(offers.len(), 1);
+        assert_eq!(offers[0].description, "Espresso");
+
+        let orders = dbt::Order::get_all(&db).unwrap();
+        assert_eq!(orders.len(), 1);
+        assert_eq!(orders[0].items[0].name, "Kava");
+        assert_eq!(orders[0].items[0].price_integer, 1);
+        assert_eq!(orders[0].items[0].price_fraction, 50);
+
+        assert_eq!(stored_version(&db).unwrap(), Some(SCHEMA_VERSION));
+
+    }
+
+    #[test]
+    fn records_of_the_current_layout_are_left_alone() {
+
+        let db = temporary_db();
+
+        let table = dbt::VirtualTable {
+            name: "1".to_string(),
+            order_count: 3,
+            zone: "Terasa".to_string(),
+            ..Default::default()
+        };
+        table.insert(&db).unwrap();
+
+        run(&db).unwrap();
+        run(&db).unwrap();
+
+        let tables = dbt::VirtualTable::get_all(&db).unwrap();
+        assert_eq!(tables.len(), 1);
+        assert_eq!(tables[0].zone, "Terasa");
+
+    }
+
+    #[test]
+    fn newer_databases_are_refused() {
+
+        let db = temporary_db();
+        db.insert(SCHEMA_KEY, &(SCHEMA_VERSION + 1).to_be_bytes()).unwrap();
+
+        assert!(run(&db).is_err());
+
+    }
+
+}
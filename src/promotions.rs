@@ -0,0 +1,250 @@
+use crate::shared::dbt as dbt;
+use crate::shared::totals;
+
+fn matches(scope: &dbt::PromotionScope, item: &dbt::OrderItem, offers: &[dbt::Offer]) -> bool {
+    match scope {
+        dbt::PromotionScope::Offer { offer } => &item.id == offer,
+        dbt::PromotionScope::Category { category } => offers
+            .iter()
+            .find(|offer| offer.name == item.id)
+            .is_some_and(|offer| offer.category.as_ref() == Some(category)),
+        dbt::PromotionScope::Order => true,
+    }
+}
+
+fn unit(item: &dbt::OrderItem) -> u64 {
+    totals::line_total(item).unit.cents()
+}
+
+/// What is left of the line's price after the discounts applied so
+/// far.
+fn remaining(item: &dbt::OrderItem) -> u64 {
+    totals::line_total(item).total.cents()
+}
+
+/// `basis_points` of `cents`, rounded to the nearest cent.
+fn percentage(cents: u64, basis_points: u32) -> u64 {
+    (cents * basis_points as u64 + 5000) / 10000
+}
+
+/// Spreads `amount` over the lines in proportion to what is left of
+/// each of them, never more than is left in total.
+fn spread(amount: u64, lines: &[usize], items: &[dbt::OrderItem]) -> Vec<(usize, u64)> {
+
+    let total: u64 = lines.iter().map(|&line| remaining(&items[line])).sum();
+    if total == 0 {
+        return vec![]
+    }
+
+    let amount = amount.min(total);
+    let mut before = 0;
+
+    lines
+        .iter()
+        .map(|&line| {
+            let after = before + remaining(&items[line]);
+            let share = amount * after / total - amount * before / total;
+            before = after;
+            (line, share)
+        })
+        .collect()
+
+}
+
+/// Of every `buy + free` pieces over all lines, `free` pieces are
+/// free, starting with the cheapest.
+fn buy_get_free(buy: u32, free: u32, lines: &[usize], items: &[dbt::OrderItem]) -> Vec<(usize, u64)> {
+
+    if free == 0 {
+        return vec![]
+    }
+
+    let pieces: u32 = lines.iter().map(|&line| items[line].count).sum();
+    let mut free_pieces = pieces / (buy + free) * free;
+
+    let mut cheapest = lines.to_vec();
+    cheapest.sort_by_key(|&line| unit(&items[line]));
+
+    cheapest
+        .into_iter()
+        .map(|line| {
+            let pieces = free_pieces.min(items[line].count);
+            free_pieces -= pieces;
+            (line, unit(&items[line]) * pieces as u64)
+        })
+        .collect()
+
+}
+
+/// Applies every promotion running at the given time to the order
+/// and records what each one took off on the order lines.
+///
+/// Promotions are applied one after another in the order given, each
+/// on what the ones before it left of the price. Discounts sent with
+/// the order are thrown away, and codes only count on orders placed
+/// by staff. Has to be called after
+/// [`crate::validation::snapshot_offers`] so the lines have prices.
+pub fn apply(
+    order: &mut dbt::Order,
+    promotions: &[dbt::Promotion],
+    offers: &[dbt::Offer],
+    (day, time): (dbt::Weekday, dbt::TimeOfDay)
+) {
+
+    for item in order.items.iter_mut() {
+        item.discounts.clear();
+    }
+
+    for promotion in promotions {
+
+        if !promotion.is_active_at(day, time) {
+            continue
+        }
+
+        if let Some(code) = &promotion.code {
+            if order.accepted_by.is_none() || !order.codes.contains(code) {
+                continue
+            }
+        }
+
+        let lines = order.items
+            .iter()
+            .enumerate()
+            .filter(|(_, item)| matches(&promotion.scope, item, offers))
+            .map(|(line, _)| line)
+            .collect::<Vec<usize>>();
+
+        let amounts = match &promotion.rule {
+            dbt::PromotionRule::Percentage { basis_points } => lines
+                .iter()
+                .map(|&line| (line, percentage(remaining(&order.items[line]), *basis_points)))
+                .collect(),
+            dbt::PromotionRule::Fixed { amount } if promotion.scope == dbt::PromotionScope::Order => {
+                spread(amount.cents(), &lines, &order.items)
+            }
+            dbt::PromotionRule::Fixed { amount } => lines
+                .iter()
+                .map(|&line| (line, amount.cents() * order.items[line].count as u64))
+                .collect(),
+            dbt::PromotionRule::BuyGetFree { buy, free } => {
+                buy_get_free(*buy, *free, &lines, &order.items)
+            }
+        };
+
+        for (line, cents) in amounts {
+            let item = &mut order.items[line];
+            let cents = cents.min(remaining(item));
+            if cents > 0 {
+                item.discounts.push(dbt::AppliedDiscount {
+                    promotion: promotion.name.clone(),
+                    amount: dbt::Amount::from_cents(cents)
+                });
+            }
+        }
+
+    }
+
+}
+
+/// Why a promotion can't be stored, if it can't.
+pub fn check(
+    promotion: &dbt::Promotion,
+    offers: &[dbt::Offer],
+    categories: &[dbt::Category]
+) -> Result<(), String> {
+
+    if promotion.name.trim().is_empty() {
+        return Err("Promotion needs a name.".to_string())
+    }
+
+    match &promotion.scope {
+        dbt::PromotionScope::Offer { offer } if !offers.iter().any(|x| &x.name == offer) => {
+            return Err("Offer does not exist!".to_string())
+        }
+        dbt::PromotionScope::Category { category } if !categories.iter().any(|x| &x.name == category) => {
+            return Err("Category does not exist!".to_string())
+        }
+        _ => ()
+    }
+
+    match promotion.rule {
+        dbt::PromotionRule::Percentage { basis_points } if !(1..=10000).contains(&basis_points) => {
+            Err("Percentage has to be between 0.01% and 100%.".to_string())
+        }
+        dbt::PromotionRule::Fixed { amount } if amount.cents() == 0 => {
+            Err("Fixed discount can't be zero.".to_string())
+        }
+        dbt::PromotionRule::BuyGetFree { buy, free } if buy == 0 || free == 0 => {
+            Err("Both bought and free pieces have to be at least one.".to_string())
+        }
+        _ => Ok(())
+    }
+
+}
+
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    fn staff_discount() -> dbt::Promotion {
+        dbt::Promotion {
+            name: "Osoblje".to_string(),
+            scope: dbt::PromotionScope::Order,
+            rule: dbt::PromotionRule::Percentage { basis_points: 3000 },
+            schedule: vec![],
+            code: Some("OSOBLJE".to_string())
+        }
+    }
+
+    fn order_with_code(accepted_by: Option<dbt::StaffID>) -> dbt::Order {
+        dbt::Order {
+            items: vec![dbt::OrderItem {
+                id: "Cedevita".to_string(),
+                count: 1,
+                name: "Cedevita".to_string(),
+                price_integer: 2,
+                price_fraction: 40,
+                ..Default::default()
+            }],
+            codes: vec!["OSOBLJE".to_string()],
+            accepted_by,
+            ..Default::default()
+        }
+    }
+
+    const MONDAY_NOON: (dbt::Weekday, dbt::TimeOfDay) = (
+        dbt::Weekday::Monday,
+        dbt::TimeOfDay { hour: 12, minute: 0 }
+    );
+
+    #[test]
+    fn guests_can_not_use_codes() {
+
+        let mut order = order_with_code(None);
+
+        apply(&mut order, &[staff_discount()], &[], MONDAY_NOON);
+
+        assert!(order.items[0].discounts.is_empty());
+
+    }
+
+    #[test]
+    fn staff_can_use_codes() {
+
+        let mut order = order_with_code(Some("ana".to_string()));
+
+        apply(&mut order, &[staff_discount()], &[], MONDAY_NOON);
+
+        assert_eq!(
+            order.items[0].discounts,
+            vec![dbt::AppliedDiscount {
+                promotion: "Osoblje".to_string(),
+                amount: dbt::Amount { integer: 0, fraction: 72 }
+            }]
+        );
+
+    }
+
+}
@@ -0,0 +1,649 @@
+use crate::billing;
+use crate::billing::BillingError;
+use crate::clock;
+use crate::db;
+use crate::db::DatabaseElement;
+use crate::fiscal;
+use crate::shared::dbt as dbt;
+use crate::shared::req_resp as req;
+use crate::shared::totals;
+
+/// Width of a receipt in characters, fits 80mm receipt paper.
+const WIDTH: usize = 40;
+
+fn venue(db: &sled::Db) -> Result<dbt::Venue, String> {
+    Ok(
+        dbt::Venue::get(dbt::Venue::default().qualified_identifier(), db)?
+            .unwrap_or_default()
+    )
+}
+
+fn next_id(db: &sled::Db) -> Result<dbt::ReceiptID, String> {
+    let year = clock::local_year(clock::timestamp());
+    let number = db::next_sequence(&format!("receipt/{}", year), db)?;
+    Ok(dbt::ReceiptID { year, number })
+}
+
+pub fn receipt_by_id(id: &dbt::ReceiptID, db: &sled::Db) -> Result<Option<dbt::Receipt>, String> {
+    dbt::Receipt::get(
+        dbt::Receipt {
+            id: id.clone(),
+            ..Default::default()
+        }.qualified_identifier(),
+        db
+    )
+}
+
+/// Refuses to issue a receipt for orders of which one is already on
+/// another receipt, an order is billed on one receipt at most.
+fn check_not_issued(orders: &[dbt::OrderID], receipts: &[dbt::Receipt]) -> Result<(), BillingError> {
+
+    for receipt in receipts {
+        if let Some(order) = orders.iter().find(|order| receipt.orders.contains(order)) {
+            return Err(BillingError::Invalid(format!(
+                "Order {} is already on receipt {}.",
+                order.count, receipt.id
+            )))
+        }
+    }
+
+    Ok(())
+
+}
+
+/// Issues the receipt for a closed bill, or returns the one that was
+/// already issued for it. Bills with an order that already has a
+/// receipt of its own are refused.
+pub fn issue_for_bill(summary: &req::BillSummary, db: &sled::Db) -> Result<dbt::Receipt, BillingError> {
+
+    if !summary.bill.closed {
+        return Err(BillingError::Invalid("Bill is not paid yet.".to_string()))
+    }
+
+    let receipts = dbt::Receipt::get_all(db)?;
+
+    if let Some(receipt) = receipts
+        .iter()
+        .find(|receipt| receipt.bill == Some(summary.bill.id))
+    {
+        return Ok(receipt.clone())
+    }
+
+    check_not_issued(&summary.bill.orders, &receipts)?;
+
+    let orders = summary.orders
+        .iter()
+        .map(|order| order.order.clone())
+        .collect::<Vec<dbt::Order>>();
+    let lines = totals::merged_lines(&orders);
+
+    let mut receipt = dbt::Receipt {
+        id: next_id(db)?,
+        issued_at: clock::timestamp(),
+        venue: venue(db)?,
+        table: summary.bill.table.clone(),
+        bill: Some(summary.bill.id),
+        orders: orders.iter().map(|order| order.id.clone()).collect(),
+        taxes: totals::tax_breakdown(&lines),
+        total: summary.total,
+        lines,
+        payments: summary.payments.clone(),
+        fiscal: None
+    };
+
+    fiscal::fiscalize_issued(&mut receipt, db)?;
+    receipt.insert(db)?;
+
+    Ok(receipt)
+
+}
+
+/// Payments of the bill that went towards the order, the ones for its
+/// items and, if the order is all the bill holds, the ones for the
+/// whole bill.
+fn payments_of_order(id: &dbt::OrderID, bill: &dbt::Bill, payments: Vec<dbt::Payment>) -> Vec<dbt::Payment> {
+    payments
+        .into_iter()
+        .filter(|payment| match payment.items.is_empty() {
+            true => bill.orders == [id.clone()],
+            false => payment.items.iter().all(|item| &item.order == id)
+        })
+        .collect()
+}
+
+/// Issues a receipt for a single paid order, or returns the one that
+/// was already issued for it. Orders that are on the receipt of their
+/// bill are refused.
+pub fn issue_for_order(id: &dbt::OrderID, db: &sled::Db) -> Result<dbt::Receipt, BillingError> {
+
+    let order = match db::order_by_id(id, db)? {
+        Some(order) => order,
+        None => return Err(BillingError::NotFound("Order doesn't exist.".to_string()))
+    };
+
+    if !order.paid {
+        return Err(BillingError::Invalid("Order is not paid yet.".to_string()))
+    }
+
+    let bill = dbt::Bill {
+        closed: true,
+        ..Default::default()
+    }
+    .get_status(db)?
+    .into_iter()
+    .find(|bill| bill.orders.contains(id));
+
+    let receipts = dbt::Receipt::get_all(db)?;
+
+    if let Some(receipt) = receipts
+        .iter()
+        .find(|receipt| receipt.bill.is_none() && receipt.orders == [id.clone()])
+    {
+        return Ok(receipt.clone())
+    }
+
+    check_not_issued(std::slice::from_ref(id), &receipts)?;
+
+    let payments = match &bill {
+        Some(bill) => payments_of_order(id, bill, billing::payments_of_bill(bill.id, db)?),
+        None => vec![]
+    };
+
+    let totals = totals::order_totals(&order);
+
+    let mut receipt = dbt::Receipt {
+        id: next_id(db)?,
+        issued_at: clock::timestamp(),
+        venue: venue(db)?,
+        table: order.id.table.clone(),
+        bill: None,
+        orders: vec![order.id.clone()],
+        lines: totals.lines,
+        taxes: totals.taxes,
+        total: totals.total,
+        payments,
+        fiscal: None
+    };
+
+    fiscal::fiscalize_issued(&mut receipt, db)?;
+    receipt.insert(db)?;
+
+    Ok(receipt)
+
+}
+
+fn method_name(method: dbt::PaymentMethod) -> &'static str {
+    match method {
+        dbt::PaymentMethod::Cash => "Cash",
+        dbt::PaymentMethod::Card => "Card",
+    }
+}
+
+fn tax_name(rate: &Option<dbt::TaxRate>) -> String {
+    match rate {
+        Some(rate) => rate.name.clone(),
+        None => "No tax".to_string()
+    }
+}
+
+fn centered(text: &str) -> String {
+    let length = text.chars().count();
+    if length >= WIDTH {
+        text.to_string()
+    } else {
+        format!("{}{}", " ".repeat((WIDTH - length) / 2), text)
+    }
+}
+
+/// Fiscalized receipts are numbered `number/premises/device`.
+fn receipt_number(receipt: &dbt::Receipt) -> String {
+    match &receipt.fiscal {
+        Some(fiscal) => format!("{}/{}/{}", receipt.id.number, fiscal.premises, fiscal.device),
+        None => receipt.id.to_string()
+    }
+}
+
+/// `left` and `right` on the same line, pushed to the edges.
+fn spread(left: &str, right: &str) -> String {
+    let used = left.chars().count() + right.chars().count();
+    if used >= WIDTH {
+        format!("{} {}", left, right)
+    } else {
+        format!("{}{}{}", left, " ".repeat(WIDTH - used), right)
+    }
+}
+
+/// The receipt as lines of plain text, [`WIDTH`] characters wide.
+pub fn render_lines(receipt: &dbt::Receipt) -> Vec<String> {
+
+    let rule = "-".repeat(WIDTH);
+    let mut lines = Vec::new();
+
+    lines.push(centered(&receipt.venue.name));
+    if !receipt.venue.address.is_empty() {
+        lines.push(centered(&receipt.venue.address));
+    }
+    if !receipt.venue.oib.is_empty() {
+        lines.push(centered(&format!("OIB: {}", receipt.venue.oib)));
+    }
+    lines.push(rule.clone());
+    lines.push(format!("Receipt no. {}", receipt_number(receipt)));
+    lines.push(format!("Table: {}", receipt.table));
+    lines.push(format!("Date: {}", clock::local_date_time_string(receipt.issued_at)));
+    lines.push(rule.clone());
+
+    for line in receipt.lines.iter() {
+        lines.push(line.name.clone());
+        if line.discount.cents() == 0 {
+            lines.push(spread(
+                &format!("  {} x {}", line.count, line.unit),
+                &line.total.to_string()
+            ));
+        } else {
+            lines.push(spread(
+                &format!("  {} x {}", line.count, line.unit),
+                &dbt::Amount::from_cents(line.total.cents() + line.discount.cents()).to_string()
+            ));
+            lines.push(spread("  Discount", &format!("-{}", line.discount)));
+        }
+    }
+
+    lines.push(rule.clone());
+    lines.push(spread("TOTAL", &receipt.total.to_string()));
+    lines.push(rule.clone());
+
+    lines.push(spread("Tax", "Base      Tax"));
+    for tax in receipt.taxes.iter() {
+        lines.push(spread(
+            &tax_name(&tax.rate),
+            &format!("{:>8} {:>8}", tax.net.to_string(), tax.tax.to_string())
+        ));
+    }
+
+    if !receipt.payments.is_empty() {
+        lines.push(rule.clone());
+        for payment in receipt.payments.iter() {
+            lines.push(spread(
+                &format!("Paid ({})", method_name(payment.method)),
+                &payment.amount.to_string()
+            ));
+            if payment.tip.cents() != 0 {
+                lines.push(spread("  Tip", &payment.tip.to_string()));
+            }
+            if let Some(tendered) = payment.tendered {
+                lines.push(spread("  Tendered", &tendered.to_string()));
+                lines.push(spread("  Change", &payment.change.to_string()));
+            }
+        }
+    }
+
+    if let Some(fiscal) = &receipt.fiscal {
+        lines.push(rule.clone());
+        lines.push(format!("Operator OIB: {}", fiscal.operator_oib));
+        lines.push(format!("ZKI: {}", fiscal.zki.as_deref().unwrap_or("-")));
+        lines.push(format!("JIR: {}", fiscal.jir.as_deref().unwrap_or("pending")));
+    }
+
+    if let Some(footer) = &receipt.venue.footer {
+        lines.push(rule.clone());
+        lines.push(centered(footer));
+    }
+
+    lines
+
+}
+
+pub fn render_text(receipt: &dbt::Receipt) -> String {
+    render_lines(receipt).join("\n") + "\n"
+}
+
+fn escape_html(text: &str) -> String {
+    text
+        .replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\'', "&#39;")
+}
+
+pub fn render_html(receipt: &dbt::Receipt) -> String {
+
+    let mut rows = String::new();
+    for line in receipt.lines.iter() {
+        rows += &format!(
+            "<tr><td>{}</td><td class=\"num\">{} x {}</td><td class=\"num\">{}</td></tr>\n",
+            escape_html(&line.name), line.count, line.unit,
+            dbt::Amount::from_cents(line.total.cents() + line.discount.cents())
+        );
+        if line.discount.cents() != 0 {
+            rows += &format!(
+                "<tr><td>Discount</td><td></td><td class=\"num\">-{}</td></tr>\n",
+                line.discount
+            );
+        }
+    }
+
+    let mut taxes = String::new();
+    for tax in receipt.taxes.iter() {
+        taxes += &format!(
+            "<tr><td>{}</td><td class=\"num\">{}</td><td class=\"num\">{}</td></tr>\n",
+            escape_html(&tax_name(&tax.rate)), tax.net, tax.tax
+        );
+    }
+
+    let mut payments = String::new();
+    for payment in receipt.payments.iter() {
+        payments += &format!(
+            "<tr><td>Paid ({})</td><td class=\"num\">{}</td></tr>\n",
+            method_name(payment.method), payment.amount
+        );
+        if payment.tip.cents() != 0 {
+            payments += &format!("<tr><td>Tip</td><td class=\"num\">{}</td></tr>\n", payment.tip);
+        }
+        if let Some(tendered) = payment.tendered {
+            payments += &format!("<tr><td>Tendered</td><td class=\"num\">{}</td></tr>\n", tendered);
+            payments += &format!("<tr><td>Change</td><td class=\"num\">{}</td></tr>\n", payment.change);
+        }
+    }
+
+    let fiscal = match &receipt.fiscal {
+        Some(fiscal) => format!(
+            "<section>\n        <div>Operator OIB: {}</div>\n        \
+            <div>ZKI: {}</div>\n        <div>JIR: {}</div>\n    </section>",
+            escape_html(&fiscal.operator_oib),
+            escape_html(fiscal.zki.as_deref().unwrap_or("-")),
+            escape_html(fiscal.jir.as_deref().unwrap_or("pending"))
+        ),
+        None => String::new()
+    };
+
+    let footer = match &receipt.venue.footer {
+        Some(footer) => format!("<p class=\"center\">{}</p>", escape_html(footer)),
+        None => String::new()
+    };
+
+    format!(
+r#"<!DOCTYPE html>
+<html lang="en">
+<head>
+    <meta charset="UTF-8">
+    <title>Receipt {id}</title>
+    <style>
+        body {{ font-family: 'Courier New', monospace; max-width: 360px; margin: 20px auto; }}
+        h1 {{ font-size: 20px; margin: 0; }}
+        .center {{ text-align: center; }}
+        .num {{ text-align: right; white-space: nowrap; }}
+        table {{ width: 100%; border-collapse: collapse; }}
+        section {{ border-top: 1px dashed #000; padding: 8px 0; }}
+        .total td {{ font-weight: bold; font-size: 18px; }}
+    </style>
+</head>
+<body>
+    <header class="center">
+        <h1>{name}</h1>
+        <div>{address}</div>
+        <div>OIB: {oib}</div>
+    </header>
+    <section>
+        <div>Receipt no. {number}</div>
+        <div>Table: {table}</div>
+        <div>Date: {date}</div>
+    </section>
+    <section>
+        <table>
+{rows}        </table>
+    </section>
+    <section>
+        <table><tr class="total"><td>TOTAL</td><td class="num">{total}</td></tr></table>
+    </section>
+    <section>
+        <table>
+            <tr><th align="left">Tax</th><th class="num">Base</th><th class="num">Tax</th></tr>
+{taxes}        </table>
+    </section>
+    <section>
+        <table>
+{payments}        </table>
+    </section>
+    {fiscal}
+    {footer}
+</body>
+</html>
+"#,
+        id = receipt.id,
+        number = escape_html(&receipt_number(receipt)),
+        name = escape_html(&receipt.venue.name),
+        address = escape_html(&receipt.venue.address),
+        oib = escape_html(&receipt.venue.oib),
+        table = escape_html(&receipt.table),
+        date = clock::local_date_time_string(receipt.issued_at),
+        total = receipt.total,
+    )
+
+}
+
+/// Maps a character to its byte in the PDF `WinAnsiEncoding`, letters
+/// the encoding doesn't have are replaced with their closest match.
+fn win_ansi(c: char) -> Vec<u8> {
+    match c {
+        '(' | ')' | '\\' => vec![b'\\', c as u8],
+        ' '..='~' => vec![c as u8],
+        '\u{a0}'..='\u{ff}' => vec![c as u32 as u8],
+        '€' => vec![0x80],
+        'Š' => vec![0x8a],
+        'š' => vec![0x9a],
+        'Ž' => vec![0x8e],
+        'ž' => vec![0x9e],
+        'Č' | 'Ć' => vec![b'C'],
+        'č' | 'ć' => vec![b'c'],
+        'Đ' => vec![b'D'],
+        'đ' => vec![b'd'],
+        _ => vec![b'?'],
+    }
+}
+
+/// The plain text receipt set in Courier on a single page as wide as
+/// a roll of receipt paper.
+pub fn render_pdf(receipt: &dbt::Receipt) -> Vec<u8> {
+
+    const FONT_SIZE: usize = 8;
+    const LEADING: usize = 10;
+    const MARGIN: usize = 17;
+
+    let lines = render_lines(receipt);
+    let width = WIDTH * FONT_SIZE * 6 / 10 + MARGIN * 2;
+    let height = lines.len() * LEADING + MARGIN * 2;
+
+    let mut content = format!(
+        "BT\n/F1 {} Tf\n{} TL\n{} {} Td\n",
+        FONT_SIZE, LEADING, MARGIN, height - MARGIN - FONT_SIZE
+    ).into_bytes();
+    for line in lines.iter() {
+        content.push(b'(');
+        content.extend(line.chars().flat_map(win_ansi));
+        content.extend(b") Tj T*\n");
+    }
+    content.extend(b"ET\n");
+
+    let mut objects: Vec<Vec<u8>> = vec![
+        b"<< /Type /Catalog /Pages 2 0 R >>".to_vec(),
+        b"<< /Type /Pages /Kids [3 0 R] /Count 1 >>".to_vec(),
+        format!(
+            "<< /Type /Page /Parent 2 0 R /MediaBox [0 0 {} {}] \
+            /Contents 4 0 R /Resources << /Font << /F1 5 0 R >> >> >>",
+            width, height
+        ).into_bytes(),
+    ];
+    let mut stream = format!("<< /Length {} >>\nstream\n", content.len()).into_bytes();
+    stream.extend(content);
+    stream.extend(b"\nendstream");
+    objects.push(stream);
+    objects.push(
+        b"<< /Type /Font /Subtype /Type1 /BaseFont /Courier /Encoding /WinAnsiEncoding >>".to_vec()
+    );
+
+    let mut pdf = b"%PDF-1.4\n".to_vec();
+    let mut offsets = Vec::new();
+    for (i, object) in objects.iter().enumerate() {
+        offsets.push(pdf.len());
+        pdf.extend(format!("{} 0 obj\n", i + 1).into_bytes());
+        pdf.extend(object);
+        pdf.extend(b"\nendobj\n");
+    }
+
+    let xref = pdf.len();
+    pdf.extend(format!("xref\n0 {}\n0000000000 65535 f \n", objects.len() + 1).into_bytes());
+    for offset in offsets {
+        pdf.extend(format!("{:010} 00000 n \n", offset).into_bytes());
+    }
+    pdf.extend(format!(
+        "trailer\n<< /Size {} /Root 1 0 R >>\nstartxref\n{}\n%%EOF\n",
+        objects.len() + 1, xref
+    ).into_bytes());
+
+    pdf
+
+}
+
+
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    fn order_id(count: u32) -> dbt::OrderID {
+        dbt::OrderID {
+            table: "1".to_string(),
+            count
+        }
+    }
+
+    fn payment(id: dbt::PaymentID, orders: &[u32]) -> dbt::Payment {
+        dbt::Payment {
+            id,
+            items: orders
+                .iter()
+                .map(|count| dbt::PaidItem {
+                    order: order_id(*count),
+                    line: 0,
+                    count: 1
+                })
+                .collect(),
+            ..Default::default()
+        }
+    }
+
+    /// A closed bill of table `1` with two paid orders.
+    fn closed_bill(db: &sled::Db) -> req::BillSummary {
+
+        dbt::VirtualTable {
+            name: "1".to_string(),
+            order_count: 2,
+            ..Default::default()
+        }.insert(db).unwrap();
+
+        for count in [1, 2] {
+            dbt::Order {
+                id: order_id(count),
+                finished: true,
+                items: vec![dbt::OrderItem {
+                    id: "Kava".to_string(),
+                    count: 2,
+                    name: "Kava".to_string(),
+                    price_integer: 1,
+                    price_fraction: 50,
+                    ..Default::default()
+                }],
+                ..Default::default()
+            }.insert(db).unwrap();
+        }
+
+        let bill = billing::open_bill(&"1".to_string(), db).unwrap();
+        billing::pay(req::BillsPayRequestData {
+            bill: bill.id,
+            method: dbt::PaymentMethod::Cash,
+            amount: None,
+            items: vec![],
+            tip: dbt::Amount::default(),
+            tendered: None
+        }, db).unwrap();
+
+        billing::close(bill.id, db).unwrap()
+
+    }
+
+    fn temporary_db() -> sled::Db {
+        sled::Config::new()
+            .temporary(true)
+            .open()
+            .expect("Failed to open a temporary database.")
+    }
+
+    #[test]
+    fn orders_of_a_closed_bill_get_their_own_receipts() {
+
+        let db = temporary_db();
+        let summary = closed_bill(&db);
+
+        let first = issue_for_order(&order_id(1), &db).unwrap();
+        let again = issue_for_order(&order_id(1), &db).unwrap();
+        assert_eq!(first.id, again.id);
+        assert_eq!(first.orders, vec![order_id(1)]);
+
+        assert!(issue_for_order(&order_id(2), &db).is_ok());
+        assert!(issue_for_bill(&summary, &db).is_err());
+
+    }
+
+    #[test]
+    fn orders_on_the_bill_receipt_get_no_receipt_of_their_own() {
+
+        let db = temporary_db();
+        let summary = closed_bill(&db);
+
+        let first = issue_for_bill(&summary, &db).unwrap();
+        let again = issue_for_bill(&summary, &db).unwrap();
+        assert_eq!(first.id, again.id);
+
+        assert!(issue_for_order(&order_id(1), &db).is_err());
+        assert_eq!(dbt::Receipt::get_all(&db).unwrap().len(), 1);
+
+    }
+
+    fn ids(payments: Vec<dbt::Payment>) -> Vec<dbt::PaymentID> {
+        payments.into_iter().map(|payment| payment.id).collect()
+    }
+
+    #[test]
+    fn orders_get_only_their_own_payments() {
+
+        let bill = dbt::Bill {
+            orders: vec![order_id(1), order_id(2)],
+            ..Default::default()
+        };
+        let payments = vec![
+            payment(1, &[1]),
+            payment(2, &[2]),
+            payment(3, &[1, 2]),
+            payment(4, &[])
+        ];
+
+        assert_eq!(ids(payments_of_order(&order_id(1), &bill, payments.clone())), vec![1]);
+        assert_eq!(ids(payments_of_order(&order_id(2), &bill, payments)), vec![2]);
+
+    }
+
+    #[test]
+    fn whole_bill_payments_belong_to_its_only_order() {
+
+        let bill = dbt::Bill {
+            orders: vec![order_id(1)],
+            ..Default::default()
+        };
+
+        assert_eq!(ids(payments_of_order(&order_id(1), &bill, vec![payment(4, &[])])), vec![4]);
+
+    }
+
+}
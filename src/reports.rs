@@ -0,0 +1,62 @@
+use crate::billing;
+use crate::clock;
+use crate::db::DatabaseElement;
+use crate::shared::dbt as dbt;
+use crate::shared::req_resp as req;
+use crate::shared::totals;
+
+/// Sums up all bills that were closed on the given local date.
+pub fn daily(date: chrono::NaiveDate, db: &sled::Db) -> Result<req::ReportsDailyResponseData, String> {
+
+    let closed = dbt::Bill {
+        closed: true,
+        ..Default::default()
+    }
+    .get_status(db)?
+    .into_iter()
+    .filter(|bill| bill.closed_at.and_then(clock::local_date) == Some(date));
+
+    let mut bills = 0;
+    let mut orders: Vec<dbt::Order> = Vec::new();
+    let mut methods: Vec<req::MethodTotal> = Vec::new();
+
+    for bill in closed {
+
+        let summary = billing::summarize(bill, db)?;
+        bills += 1;
+
+        orders.extend(summary.orders.into_iter().map(|order| order.order));
+
+        for payment in summary.payments {
+            let position = match methods.iter().position(|total| total.method == payment.method) {
+                Some(position) => position,
+                None => {
+                    methods.push(req::MethodTotal {
+                        method: payment.method,
+                        amount: dbt::Amount::default(),
+                        tips: dbt::Amount::default()
+                    });
+                    methods.len() - 1
+                }
+            };
+            let total = &mut methods[position];
+            total.amount = dbt::Amount::from_cents(total.amount.cents() + payment.amount.cents());
+            total.tips = dbt::Amount::from_cents(total.tips.cents() + payment.tip.cents());
+        }
+
+    }
+
+    let lines = totals::merged_lines(&orders);
+    let taxes = totals::tax_breakdown(&lines);
+
+    Ok(req::ReportsDailyResponseData {
+        date: date.format("%Y-%m-%d").to_string(),
+        bills,
+        total: dbt::Amount::from_cents(lines.iter().map(|line| line.total.cents()).sum()),
+        tips: dbt::Amount::from_cents(methods.iter().map(|total| total.tips.cents()).sum()),
+        methods,
+        lines,
+        taxes
+    })
+
+}
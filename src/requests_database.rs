@@ -1,5 +1,4 @@
 use std::collections::HashMap;
-use std::collections::HashSet;
 use std::sync::Arc;
 use std::sync::Mutex;
 
@@ -9,13 +8,33 @@ use actix_web::post;
 use actix_web::web;
 use actix_web::Responder;
 
+use crate::alerts;
+use crate::auth;
+use crate::billing;
+use crate::clock;
+use crate::db;
 use crate::db::DatabaseElement;
+use crate::fiscal;
+use crate::guest;
+use crate::i18n;
+use crate::inventory;
+use crate::promotions;
+use crate::receipt;
+use crate::sections;
+use crate::session;
+use crate::tickets;
+use crate::reports;
+use crate::reservations;
 use crate::shared::dbt as dbt;
 use crate::shared::logging::logf;
 use crate::shared::req_resp as req;
+use crate::shared::totals;
+use crate::transfer;
+use crate::validation;
 
 #[get("/tables")]
 pub async fn handler_tables(
+    filter: web::Query<req::TablesRequestData>,
     db: web::Data<Arc<Mutex<sled::Db>>>
 ) -> impl Responder {
 
@@ -31,7 +50,7 @@ pub async fn handler_tables(
     };
 
     let template = dbt::VirtualTable{..Default::default()};
-    let tables =
+    let mut tables =
     match template.get_templated(&*db_locked) {
         Ok(tables) => tables,
         Err(_) => {
@@ -40,6 +59,13 @@ pub async fn handler_tables(
                 .body("Database failed.")
         }
     };
+
+    let filter = filter.into_inner();
+    tables.retain(|table| {
+        filter.zone.as_ref().is_none_or(|zone| &table.zone == zone)
+        && filter.state.is_none_or(|state| table.state == state)
+    });
+    tables.sort_by(|a, b| (a.position, &a.name).cmp(&(b.position, &b.name)));
         
 
     log::info!("{}", logf!("Exited."));
@@ -110,7 +136,27 @@ pub async fn handler_tables_insert(
         }
     };
 
-    match request_data.into_inner().table.insert(&db_locked) {
+    let mut table = request_data.into_inner().table;
+
+    // The order count, state and generation are kept by the server,
+    // an edit made from a stale copy of the table would otherwise
+    // reuse order numbers, undo a state change or bring back revoked
+    // guest links.
+    match dbt::VirtualTable::get(table.qualified_identifier(), &db_locked) {
+        Ok(existing) => {
+            let existing = existing.unwrap_or_default();
+            table.order_count = existing.order_count;
+            table.state = existing.state;
+            table.token_generation = existing.token_generation;
+        }
+        Err(err) => {
+            log::error!("{}: {}", logf!("Failed to read value from database!"), err);
+            return actix_web::HttpResponse::InternalServerError()
+                .body("Database failed.")
+        }
+    }
+
+    match table.insert(&db_locked) {
         Ok(()) => {
             return actix_web::HttpResponse::Ok()
                 .body("Successfully created the table.")
@@ -125,9 +171,9 @@ pub async fn handler_tables_insert(
 }
 
 
-#[delete("/tables-{id}")]
-pub async fn handler_tables_delete(
-    table_id: web::Path<dbt::VirtualTableID>,
+#[post("/tables-state")]
+pub async fn handler_tables_state(
+    request_data: web::Json<req::TablesStateRequestData>,
     db: web::Data<Arc<Mutex<sled::Db>>>
 ) -> impl Responder { 
 
@@ -142,109 +188,118 @@ pub async fn handler_tables_delete(
         }
     };
 
-    let template =  dbt::VirtualTable {
-        name: table_id.into_inner(),
-        ..Default::default()
-    };
+    let data = request_data.into_inner();
 
-    match template.remove(&db_locked){
-        Ok(()) => {
-            return actix_web::HttpResponse::Ok()
-                .body("Successfully removed the table.")         
-        }
+    match db::set_table_state(&data.table, data.state, &db_locked) {
+        Ok(true) => actix_web::HttpResponse::Ok()
+            .body("Successfully updated the table."),
+        Ok(false) => actix_web::HttpResponse::NotFound()
+            .body("Table does not exist!"),
         Err(err) => {
-            log::error!("{}: {}", logf!("Failed to lock database!"), err);
-            return actix_web::HttpResponse::InternalServerError()
+            log::error!("{}: {}", logf!("Failed to write value to database!"), err);
+            actix_web::HttpResponse::InternalServerError()
                 .body("Database failed.")
         }
     }
 
 }
 
-#[get("/offers")]
-pub async fn handler_offers(
+#[get("/tables-{id}/token")]
+pub async fn handler_tables_token(
+    table_id: web::Path<dbt::VirtualTableID>,
     db: web::Data<Arc<Mutex<sled::Db>>>
-) -> impl Responder { 
+) -> impl Responder {
 
     log::info!("{}", logf!("Entered."));
 
     let db_locked = match db.lock() {
         Ok(lock) => lock,
-        Err(err) => {
-            log::error!("{}: {}", logf!("Failed to lock database!"), err);
+        Err(_) => {
+            log::error!("{}", logf!("Failed to lock database!"));
             return actix_web::HttpResponse::InternalServerError()
                 .body("Database is in a deadlock. (DEADLOCK REFERENCE 😳)")
         }
     };
 
-    let probable_offers = dbt::Offer {
-        ..Default::default()
-    }.get_templated(&*db_locked);
+    let table = dbt::VirtualTable::get(
+        dbt::VirtualTable {
+            name: table_id.into_inner(),
+            ..Default::default()
+        }.qualified_identifier(),
+        &db_locked
+    );
 
-    let offers = match probable_offers {
-        Ok(offers) => offers,
+    match table.and_then(|table| Ok((table, guest::signing_key(&db_locked)?))) {
+        Ok((Some(table), key)) => {
+            let ip = req::get_local_ip_address().unwrap_or_else(|_| req::IP.to_string());
+            actix_web::HttpResponse::Ok()
+                .json(guest::guest_link(&ip, &table, &key))
+        }
+        Ok((None, _)) => actix_web::HttpResponse::NotFound()
+            .body("Table does not exist!"),
         Err(err) => {
-            log::error!("{}: {}", logf!("No offers found!"), err);
-            return actix_web::HttpResponse::InternalServerError()
-                .body("Failed to get offers due to a database error.")
-        } 
-    };
-
-    return actix_web::HttpResponse::Ok()
-        .json(req::OffersResponseData {offers})
+            log::error!("{}: {}", logf!("Failed to read value from database!"), err);
+            actix_web::HttpResponse::InternalServerError()
+                .body("Database failed.")
+        }
+    }
 
 }
 
-#[get("/offers/{id}")]
-pub async fn handler_offers_specific(
-    user_offer: web::Path<dbt::OfferID>,
+#[post("/tables-token-rotate")]
+pub async fn handler_tables_token_rotate(
+    request_data: web::Json<req::TablesTokenRotateRequestData>,
     db: web::Data<Arc<Mutex<sled::Db>>>
-) -> impl Responder { 
+) -> impl Responder {
 
     log::info!("{}", logf!("Entered."));
 
     let db_locked = match db.lock() {
         Ok(lock) => lock,
-        Err(err) => {
-            log::error!("{}: {}", logf!("Failed to lock database!"), err);
+        Err(_) => {
+            log::error!("{}", logf!("Failed to lock database!"));
             return actix_web::HttpResponse::InternalServerError()
-                .reason("Database is in a deadlock. (DEADLOCK REFERENCE 😳)")
-                .body("")
+                .body("Database is in a deadlock. (DEADLOCK REFERENCE 😳)")
         }
     };
 
-    match dbt::Offer::get(
-        dbt::Offer {
-            name: user_offer.into_inner(),
+    let table = dbt::VirtualTable::get(
+        dbt::VirtualTable {
+            name: request_data.into_inner().table,
             ..Default::default()
-        }.qualified_identifier(), 
-        &*db_locked
-    ) {
-        Ok(Some(offer)) => {
-            return actix_web::HttpResponse::Ok()
-                .json(req::OffersSpecificResponseData {offer})
+        }.qualified_identifier(),
+        &db_locked
+    );
+
+    let rotated = table.and_then(|table| match table {
+        Some(mut table) => {
+            table.token_generation += 1;
+            table.insert(&db_locked)?;
+            Ok(Some((table, guest::signing_key(&db_locked)?)))
         }
-        Ok(None) => {
-            log::error!("{}", logf!("No offer found!"));
-            return actix_web::HttpResponse::NotFound()
-                .reason("Does not exist!")
-                .body("")
+        None => Ok(None)
+    });
+
+    match rotated {
+        Ok(Some((table, key))) => {
+            let ip = req::get_local_ip_address().unwrap_or_else(|_| req::IP.to_string());
+            actix_web::HttpResponse::Ok()
+                .json(guest::guest_link(&ip, &table, &key))
         }
+        Ok(None) => actix_web::HttpResponse::NotFound()
+            .body("Table does not exist!"),
         Err(err) => {
-            log::error!("{}: {}", logf!("No offers found!"), err);
-            return actix_web::HttpResponse::InternalServerError()
-                .reason("Failed to get offers due to a database error.")
-                .body("")
-        } 
+            log::error!("{}: {}", logf!("Failed to write value to database!"), err);
+            actix_web::HttpResponse::InternalServerError()
+                .body("Database failed.")
+        }
     }
 
 }
 
-
-
-#[post("/offers")]
-pub async fn handler_offers_insert(
-    request_data: web::Json<req::OffersInsertRequestData>,
+#[delete("/tables-{id}")]
+pub async fn handler_tables_delete(
+    table_id: web::Path<dbt::VirtualTableID>,
     db: web::Data<Arc<Mutex<sled::Db>>>
 ) -> impl Responder { 
 
@@ -259,10 +314,15 @@ pub async fn handler_offers_insert(
         }
     };
 
-    match request_data.into_inner().offer.insert(&db_locked) {
+    let template =  dbt::VirtualTable {
+        name: table_id.into_inner(),
+        ..Default::default()
+    };
+
+    match template.remove(&db_locked){
         Ok(()) => {
             return actix_web::HttpResponse::Ok()
-                .body("Successfully created the offer.")
+                .body("Successfully removed the table.")         
         }
         Err(err) => {
             log::error!("{}: {}", logf!("Failed to lock database!"), err);
@@ -273,10 +333,9 @@ pub async fn handler_offers_insert(
 
 }
 
-
-#[delete("/offers/{id}")]
-pub async fn handler_offers_delete(
-    offer_id: web::Path<dbt::OfferID>,
+#[get("/tables-{id}/bill")]
+pub async fn handler_tables_bill(
+    table_id: web::Path<dbt::VirtualTableID>,
     db: web::Data<Arc<Mutex<sled::Db>>>
 ) -> impl Responder { 
 
@@ -291,28 +350,58 @@ pub async fn handler_offers_delete(
         }
     };
 
-    let template =  dbt::Offer {
-        name: offer_id.into_inner(),
+    let table = table_id.into_inner();
+
+    let template = dbt::VirtualTable {
+        name: table.clone(),
         ..Default::default()
     };
 
-    match template.remove(&db_locked){
-        Ok(()) => {
-            return actix_web::HttpResponse::Ok()
-                .body("Successfully removed the table.")         
+    match template.exists(&db_locked) {
+        Ok(true) => (),
+        Ok(false) => {
+            log::info!("{}", logf!("Nothing found."));
+            return actix_web::HttpResponse::NotFound()
+                .body("Table does not exist!")
         }
         Err(err) => {
-            log::error!("{}: {}", logf!("Failed to lock database!"), err);
+            log::error!("{}: {}", logf!("Failed to read value from database!"), err);
             return actix_web::HttpResponse::InternalServerError()
                 .body("Database failed.")
         }
     }
 
+    let orders = match dbt::Order::get_all(&db_locked) {
+        Ok(orders) => orders
+            .into_iter()
+            .filter(|order| order.id.table == table && !order.paid)
+            .collect::<Vec<dbt::Order>>(),
+        Err(err) => {
+            log::error!("{}: {}", logf!("Failed to read orders!"), err);
+            return actix_web::HttpResponse::InternalServerError()
+                .body("Database failed.")
+        }
+    };
+
+    let lines = totals::merged_lines(&orders);
+    let taxes = totals::tax_breakdown(&lines);
+    let total = dbt::Amount::from_cents(
+        lines.iter().map(|line| line.total.cents()).sum()
+    );
+
+    actix_web::HttpResponse::Ok()
+        .json(req::TablesBillResponseData {
+            table,
+            orders: orders.into_iter().map(req::TotaledOrder::from).collect(),
+            lines,
+            taxes,
+            total
+        })
+
 }
 
-#[get("/orders")]
-pub async fn handler_orders(
-    data: web::Json<req::OrdersRequestData>,
+#[get("/offers")]
+pub async fn handler_offers(
     db: web::Data<Arc<Mutex<sled::Db>>>
 ) -> impl Responder { 
 
@@ -327,37 +416,12 @@ pub async fn handler_orders(
         }
     };
 
-    
-    let probable_orders = if !data.new && data.table.is_none() {
-        dbt::Order::get_all(&*db_locked)
-    } else {
-        if data.table.is_some() {
-
-            dbt::Order {
-                id: dbt::OrderID {
-                    count: 0,
-                    table: data.table.clone().unwrap()
-                },
-                finished: !data.new,
-                items: vec![]
-            }.get_templated(&*db_locked) 
-
-        } else {
-
-            dbt::Order {
-                id: dbt::OrderID {
-                    count: 0,
-                    table: "".to_string()
-                },
-                finished: !data.new,
-                items: vec![]
-            }.get_status(&*db_locked) 
-            
-        }
-    };
+    let probable_offers = dbt::Offer {
+        ..Default::default()
+    }.get_templated(&*db_locked);
 
-    let orders = match probable_orders {
-        Ok(orders) => orders,
+    let offers = match probable_offers {
+        Ok(offers) => offers,
         Err(err) => {
             log::error!("{}: {}", logf!("No offers found!"), err);
             return actix_web::HttpResponse::InternalServerError()
@@ -365,15 +429,14 @@ pub async fn handler_orders(
         } 
     };
 
-
     return actix_web::HttpResponse::Ok()
-        .json(req::OrdersResponseData {orders})
+        .json(req::OffersResponseData {offers})
 
 }
 
-#[get("/orders/specific")]
-pub async fn handler_orders_specific(
-    data: web::Json<req::OrdersSpecificRequestData>,
+#[get("/offers/{id}")]
+pub async fn handler_offers_specific(
+    user_offer: web::Path<dbt::OfferID>,
     db: web::Data<Arc<Mutex<sled::Db>>>
 ) -> impl Responder { 
 
@@ -389,13 +452,16 @@ pub async fn handler_orders_specific(
         }
     };
 
-    match dbt::Order::get(
-        data.into_inner().order.qualified_identifier(), 
+    match dbt::Offer::get(
+        dbt::Offer {
+            name: user_offer.into_inner(),
+            ..Default::default()
+        }.qualified_identifier(), 
         &*db_locked
     ) {
-        Ok(Some(order)) => {
+        Ok(Some(offer)) => {
             return actix_web::HttpResponse::Ok()
-                .json(req::OrdersSpecificResponseData {order})
+                .json(req::OffersSpecificResponseData {offer})
         }
         Ok(None) => {
             log::error!("{}", logf!("No offer found!"));
@@ -415,9 +481,9 @@ pub async fn handler_orders_specific(
 
 
 
-#[post("/orders")]
-pub async fn handler_orders_insert(
-    data: web::Json<req::OrdersInsertRequestData>,
+#[post("/offers")]
+pub async fn handler_offers_insert(
+    request_data: web::Json<req::OffersInsertRequestData>,
     db: web::Data<Arc<Mutex<sled::Db>>>
 ) -> impl Responder { 
 
@@ -432,37 +498,77 @@ pub async fn handler_orders_insert(
         }
     };
 
-    let mut template = data.into_inner().order;
+    let offer = request_data.into_inner().offer;
 
-    let mut table = match dbt::VirtualTable::get(
-        dbt::VirtualTable {
-            name: template.clone().id.table,
+    if let Some(response) = check_station(&offer.station, &db_locked) {
+        return response
+    }
+
+    if let Some(category) = &offer.category {
+        let template = dbt::Category {
+            name: category.clone(),
             ..Default::default()
-        }.qualified_identifier(), 
-        &db_locked
-    ) {
-        Ok(Some(table)) => table,
-        _ => {
-            return actix_web::HttpResponse::InternalServerError()
-                .body("Database failed.")
+        };
+        match template.exists(&db_locked) {
+            Ok(true) => (),
+            Ok(false) => {
+                return actix_web::HttpResponse::BadRequest()
+                    .body("Category does not exist!")
+            }
+            Err(err) => {
+                log::error!("{}: {}", logf!("Failed to read value from database!"), err);
+                return actix_web::HttpResponse::InternalServerError()
+                    .body("Database failed.")
+            }
         }
-    };
+    }
 
-    table.order_count = table.order_count+1;
-    template.id.count = table.order_count;
+    if let Some(tax_rate) = &offer.tax_rate {
+        let template = dbt::TaxRate {
+            name: tax_rate.clone(),
+            ..Default::default()
+        };
+        match template.exists(&db_locked) {
+            Ok(true) => (),
+            Ok(false) => {
+                return actix_web::HttpResponse::BadRequest()
+                    .body("Tax rate does not exist!")
+            }
+            Err(err) => {
+                log::error!("{}: {}", logf!("Failed to read value from database!"), err);
+                return actix_web::HttpResponse::InternalServerError()
+                    .body("Database failed.")
+            }
+        }
+    }
 
-    match table.insert(&db_locked) {
-        Ok(()) => (),
-        _ => {
+    let stock = match dbt::StockItem::get_all(&db_locked) {
+        Ok(stock) => stock,
+        Err(err) => {
+            log::error!("{}: {}", logf!("Failed to read stock!"), err);
             return actix_web::HttpResponse::InternalServerError()
                 .body("Database failed.")
         }
+    };
+
+    for ingredient in offer.recipe.iter() {
+        if !stock.iter().any(|item| item.name == ingredient.stock) {
+            return actix_web::HttpResponse::BadRequest()
+                .body(format!("Stock item `{}` does not exist!", ingredient.stock))
+        }
+        if ingredient.quantity <= 0 {
+            return actix_web::HttpResponse::BadRequest()
+                .body("Recipe quantities have to be positive.")
+        }
     }
 
-    match template.insert(&db_locked) {
+    let mut offer = offer;
+    offer.out_of_stock = inventory::is_out_of_stock(&offer, &stock);
+
+    match offer.insert(&db_locked) {
         Ok(()) => {
             return actix_web::HttpResponse::Ok()
-                .body("Successfully created the order.")
+                .body("Successfully created the offer.")
         }
         Err(err) => {
             log::error!("{}: {}", logf!("Failed to lock database!"), err);
@@ -474,9 +580,9 @@ pub async fn handler_orders_insert(
 }
 
 
-#[delete("/orders")]
-pub async fn handler_orders_delete(
-    data: web::Json<req::OrdersDeleteRequestData>,
+#[delete("/offers/{id}")]
+pub async fn handler_offers_delete(
+    offer_id: web::Path<dbt::OfferID>,
     db: web::Data<Arc<Mutex<sled::Db>>>
 ) -> impl Responder { 
 
@@ -491,7 +597,12 @@ pub async fn handler_orders_delete(
         }
     };
 
-    match data.into_inner().order.remove(&db_locked){
+    let template =  dbt::Offer {
+        name: offer_id.into_inner(),
+        ..Default::default()
+    };
+
+    match template.remove(&db_locked){
         Ok(()) => {
             return actix_web::HttpResponse::Ok()
                 .body("Successfully removed the table.")         
@@ -505,11 +616,10 @@ pub async fn handler_orders_delete(
 
 }
 
-#[post("/orders-finish")]
-pub async fn handler_orders_finish(
-    data: web::Json<req::OrdersDeleteRequestData>,
+#[get("/stations")]
+pub async fn handler_stations(
     db: web::Data<Arc<Mutex<sled::Db>>>
-) -> impl Responder { 
+) -> impl Responder {
 
     log::info!("{}", logf!("Entered."));
 
@@ -522,32 +632,1367 @@ pub async fn handler_orders_finish(
         }
     };
 
-    let template = data.into_inner().order;
-
-    let order = match dbt::Order::get(
-        template.qualified_identifier(),
-        &db_locked
-    ) {
-        Ok(Some(mut order)) => {
-            order.finished = true;
-            order
-        },
-        Ok(None) | Err(_) => {
-            return actix_web::HttpResponse::InternalServerError()
-                .body("Order doesn't exist.")
-        }
-    };
-
-    match template.remove(&db_locked){
-        Ok(()) => (),
+    match dbt::Station::get_all(&db_locked) {
+        Ok(stations) => actix_web::HttpResponse::Ok()
+            .json(req::StationsResponseData {stations}),
         Err(err) => {
-            log::error!("{}: {}", logf!("Failed to lock database!"), err);
-            return actix_web::HttpResponse::InternalServerError()
+            log::error!("{}: {}", logf!("Failed to read value from database!"), err);
+            actix_web::HttpResponse::InternalServerError()
                 .body("Database failed.")
         }
     }
 
-    match order.insert(&db_locked) {
+}
+
+#[post("/stations")]
+pub async fn handler_stations_insert(
+    request_data: web::Json<req::StationsInsertRequestData>,
+    db: web::Data<Arc<Mutex<sled::Db>>>
+) -> impl Responder {
+
+    log::info!("{}", logf!("Entered."));
+
+    let db_locked = match db.lock() {
+        Ok(lock) => lock,
+        Err(_) => {
+            log::error!("{}", logf!("Failed to lock database!"));
+            return actix_web::HttpResponse::InternalServerError()
+                .body("Database is in a deadlock. (DEADLOCK REFERENCE 😳)")
+        }
+    };
+
+    let station = request_data.into_inner().station;
+
+    if station.name.trim().is_empty() || station.name.contains('/') {
+        return actix_web::HttpResponse::BadRequest()
+            .body("Name can't be empty or contain `/`.")
+    }
+
+    match station.insert(&db_locked) {
+        Ok(()) => actix_web::HttpResponse::Ok()
+            .body("Successfully created the station."),
+        Err(err) => {
+            log::error!("{}: {}", logf!("Failed to write value to database!"), err);
+            actix_web::HttpResponse::InternalServerError()
+                .body("Database failed.")
+        }
+    }
+
+}
+
+#[delete("/stations/{id}")]
+pub async fn handler_stations_delete(
+    station_id: web::Path<dbt::StationID>,
+    db: web::Data<Arc<Mutex<sled::Db>>>
+) -> impl Responder {
+
+    log::info!("{}", logf!("Entered."));
+
+    let db_locked = match db.lock() {
+        Ok(lock) => lock,
+        Err(_) => {
+            log::error!("{}", logf!("Failed to lock database!"));
+            return actix_web::HttpResponse::InternalServerError()
+                .body("Database is in a deadlock. (DEADLOCK REFERENCE 😳)")
+        }
+    };
+
+    let station = station_id.into_inner();
+
+    let in_use = dbt::Offer::get_all(&db_locked)
+        .and_then(|offers| Ok((offers, dbt::Category::get_all(&db_locked)?)))
+        .map(|(offers, categories)| {
+            offers.iter().any(|offer| offer.station.as_ref() == Some(&station))
+            || categories.iter().any(|category| category.station.as_ref() == Some(&station))
+        });
+
+    match in_use {
+        Ok(false) => (),
+        Ok(true) => {
+            return actix_web::HttpResponse::BadRequest()
+                .body("Station is still used by offers or categories.")
+        }
+        Err(err) => {
+            log::error!("{}: {}", logf!("Failed to read value from database!"), err);
+            return actix_web::HttpResponse::InternalServerError()
+                .body("Database failed.")
+        }
+    }
+
+    match (dbt::Station {name: station}).remove(&db_locked) {
+        Ok(()) => actix_web::HttpResponse::Ok()
+            .body("Successfully removed the station."),
+        Err(err) => {
+            log::error!("{}: {}", logf!("Failed to remove value from database!"), err);
+            actix_web::HttpResponse::InternalServerError()
+                .body("Database failed.")
+        }
+    }
+
+}
+
+#[get("/stations-{id}/tickets")]
+pub async fn handler_stations_tickets(
+    station_id: web::Path<dbt::StationID>,
+    filter: web::Query<req::StationsTicketsRequestData>,
+    db: web::Data<Arc<Mutex<sled::Db>>>
+) -> impl Responder {
+
+    log::info!("{}", logf!("Entered."));
+
+    let db_locked = match db.lock() {
+        Ok(lock) => lock,
+        Err(_) => {
+            log::error!("{}", logf!("Failed to lock database!"));
+            return actix_web::HttpResponse::InternalServerError()
+                .body("Database is in a deadlock. (DEADLOCK REFERENCE 😳)")
+        }
+    };
+
+    let station = station_id.into_inner();
+
+    match (dbt::Station {name: station.clone()}).exists(&db_locked) {
+        Ok(true) => (),
+        Ok(false) => {
+            return actix_web::HttpResponse::NotFound()
+                .body("Station does not exist!")
+        }
+        Err(err) => {
+            log::error!("{}: {}", logf!("Failed to read value from database!"), err);
+            return actix_web::HttpResponse::InternalServerError()
+                .body("Database failed.")
+        }
+    }
+
+    match tickets::queue(&station, filter.served, &db_locked) {
+        Ok(tickets) => actix_web::HttpResponse::Ok()
+            .json(req::StationsTicketsResponseData {
+                station,
+                tickets,
+                now: clock::timestamp()
+            }),
+        Err(err) => {
+            log::error!("{}: {}", logf!("Failed to read value from database!"), err);
+            actix_web::HttpResponse::InternalServerError()
+                .body("Database failed.")
+        }
+    }
+
+}
+
+#[post("/tickets-bump")]
+pub async fn handler_tickets_bump(
+    request_data: web::Json<req::TicketsBumpRequestData>,
+    authenticated: web::ReqData<auth::Authenticated>,
+    db: web::Data<Arc<Mutex<sled::Db>>>
+) -> impl Responder {
+
+    log::info!("{}", logf!("Entered."));
+
+    let db_locked = match db.lock() {
+        Ok(lock) => lock,
+        Err(_) => {
+            log::error!("{}", logf!("Failed to lock database!"));
+            return actix_web::HttpResponse::InternalServerError()
+                .body("Database is in a deadlock. (DEADLOCK REFERENCE 😳)")
+        }
+    };
+
+    let request_data = request_data.into_inner();
+
+    let ticket = match tickets::ticket_by_id(request_data.ticket, &db_locked) {
+        Ok(Some(ticket)) => ticket,
+        Ok(None) => {
+            return actix_web::HttpResponse::NotFound()
+                .body("Ticket does not exist!")
+        }
+        Err(err) => {
+            log::error!("{}: {}", logf!("Failed to read value from database!"), err);
+            return actix_web::HttpResponse::InternalServerError()
+                .body("Database failed.")
+        }
+    };
+
+    let bumped = tickets::bump(ticket, request_data.line, request_data.status, &db_locked)
+        .and_then(|bumped| match bumped {
+            Ok(ticket) => {
+                tickets::finish_if_served(&ticket.order, &authenticated.staff.name, &db_locked)?;
+                Ok(Ok(ticket))
+            }
+            Err(reason) => Ok(Err(reason))
+        });
+
+    match bumped {
+        Ok(Ok(ticket)) => actix_web::HttpResponse::Ok()
+            .json(ticket),
+        Ok(Err(reason)) => actix_web::HttpResponse::BadRequest()
+            .body(reason),
+        Err(err) => {
+            log::error!("{}: {}", logf!("Failed to write value to database!"), err);
+            actix_web::HttpResponse::InternalServerError()
+                .body("Database failed.")
+        }
+    }
+
+}
+
+#[get("/categories")]
+pub async fn handler_categories(
+    db: web::Data<Arc<Mutex<sled::Db>>>
+) -> impl Responder { 
+
+    log::info!("{}", logf!("Entered."));
+
+    let db_locked = match db.lock() {
+        Ok(lock) => lock,
+        Err(_) => {
+            log::error!("{}", logf!("Failed to lock database!"));
+            return actix_web::HttpResponse::InternalServerError()
+                .body("Database is in a deadlock. (DEADLOCK REFERENCE 😳)")
+        }
+    };
+
+    match dbt::Category::get_all(&db_locked) {
+        Ok(categories) => actix_web::HttpResponse::Ok()
+            .json(req::CategoriesResponseData {categories}),
+        Err(err) => {
+            log::error!("{}: {}", logf!("Failed to read value from database!"), err);
+            actix_web::HttpResponse::InternalServerError()
+                .body("Database failed.")
+        }
+    }
+
+}
+
+#[post("/categories")]
+pub async fn handler_categories_insert(
+    request_data: web::Json<req::CategoriesInsertRequestData>,
+    db: web::Data<Arc<Mutex<sled::Db>>>
+) -> impl Responder { 
+
+    log::info!("{}", logf!("Entered."));
+
+    let db_locked = match db.lock() {
+        Ok(lock) => lock,
+        Err(_) => {
+            log::error!("{}", logf!("Failed to lock database!"));
+            return actix_web::HttpResponse::InternalServerError()
+                .body("Database is in a deadlock. (DEADLOCK REFERENCE 😳)")
+        }
+    };
+
+    let category = request_data.into_inner().category;
+
+    if let Some(response) = check_station(&category.station, &db_locked) {
+        return response
+    }
+
+    if let Some(tax_rate) = &category.tax_rate {
+        let template = dbt::TaxRate {
+            name: tax_rate.clone(),
+            ..Default::default()
+        };
+        match template.exists(&db_locked) {
+            Ok(true) => (),
+            Ok(false) => {
+                return actix_web::HttpResponse::BadRequest()
+                    .body("Tax rate does not exist!")
+            }
+            Err(err) => {
+                log::error!("{}: {}", logf!("Failed to read value from database!"), err);
+                return actix_web::HttpResponse::InternalServerError()
+                    .body("Database failed.")
+            }
+        }
+    }
+
+    match category.insert(&db_locked) {
+        Ok(()) => actix_web::HttpResponse::Ok()
+            .body("Successfully created the category."),
+        Err(err) => {
+            log::error!("{}: {}", logf!("Failed to write value to database!"), err);
+            actix_web::HttpResponse::InternalServerError()
+                .body("Database failed.")
+        }
+    }
+
+}
+
+#[delete("/categories/{id}")]
+pub async fn handler_categories_delete(
+    category_id: web::Path<dbt::CategoryID>,
+    db: web::Data<Arc<Mutex<sled::Db>>>
+) -> impl Responder { 
+
+    log::info!("{}", logf!("Entered."));
+
+    let db_locked = match db.lock() {
+        Ok(lock) => lock,
+        Err(_) => {
+            log::error!("{}", logf!("Failed to lock database!"));
+            return actix_web::HttpResponse::InternalServerError()
+                .body("Database is in a deadlock. (DEADLOCK REFERENCE 😳)")
+        }
+    };
+
+    let template = dbt::Category {
+        name: category_id.into_inner(),
+        ..Default::default()
+    };
+
+    match template.remove(&db_locked) {
+        Ok(()) => actix_web::HttpResponse::Ok()
+            .body("Successfully removed the category."),
+        Err(err) => {
+            log::error!("{}: {}", logf!("Failed to remove value from database!"), err);
+            actix_web::HttpResponse::InternalServerError()
+                .body("Database failed.")
+        }
+    }
+
+}
+
+#[get("/promotions")]
+pub async fn handler_promotions(
+    db: web::Data<Arc<Mutex<sled::Db>>>
+) -> impl Responder { 
+
+    log::info!("{}", logf!("Entered."));
+
+    let db_locked = match db.lock() {
+        Ok(lock) => lock,
+        Err(_) => {
+            log::error!("{}", logf!("Failed to lock database!"));
+            return actix_web::HttpResponse::InternalServerError()
+                .body("Database is in a deadlock. (DEADLOCK REFERENCE 😳)")
+        }
+    };
+
+    match dbt::Promotion::get_all(&db_locked) {
+        Ok(promotions) => actix_web::HttpResponse::Ok()
+            .json(req::PromotionsResponseData {promotions}),
+        Err(err) => {
+            log::error!("{}: {}", logf!("Failed to read value from database!"), err);
+            actix_web::HttpResponse::InternalServerError()
+                .body("Database failed.")
+        }
+    }
+
+}
+
+#[post("/promotions")]
+pub async fn handler_promotions_insert(
+    request_data: web::Json<req::PromotionsInsertRequestData>,
+    db: web::Data<Arc<Mutex<sled::Db>>>
+) -> impl Responder { 
+
+    log::info!("{}", logf!("Entered."));
+
+    let db_locked = match db.lock() {
+        Ok(lock) => lock,
+        Err(_) => {
+            log::error!("{}", logf!("Failed to lock database!"));
+            return actix_web::HttpResponse::InternalServerError()
+                .body("Database is in a deadlock. (DEADLOCK REFERENCE 😳)")
+        }
+    };
+
+    let promotion = request_data.into_inner().promotion;
+
+    let checked = dbt::Offer::get_all(&db_locked)
+        .and_then(|offers| Ok((offers, dbt::Category::get_all(&db_locked)?)))
+        .map(|(offers, categories)| promotions::check(&promotion, &offers, &categories));
+
+    match checked {
+        Ok(Ok(())) => (),
+        Ok(Err(reason)) => {
+            return actix_web::HttpResponse::BadRequest()
+                .body(reason)
+        }
+        Err(err) => {
+            log::error!("{}: {}", logf!("Failed to read value from database!"), err);
+            return actix_web::HttpResponse::InternalServerError()
+                .body("Database failed.")
+        }
+    }
+
+    match promotion.insert(&db_locked) {
+        Ok(()) => actix_web::HttpResponse::Ok()
+            .body("Successfully created the promotion."),
+        Err(err) => {
+            log::error!("{}: {}", logf!("Failed to write value to database!"), err);
+            actix_web::HttpResponse::InternalServerError()
+                .body("Database failed.")
+        }
+    }
+
+}
+
+#[delete("/promotions/{id}")]
+pub async fn handler_promotions_delete(
+    promotion_id: web::Path<dbt::PromotionID>,
+    db: web::Data<Arc<Mutex<sled::Db>>>
+) -> impl Responder { 
+
+    log::info!("{}", logf!("Entered."));
+
+    let db_locked = match db.lock() {
+        Ok(lock) => lock,
+        Err(_) => {
+            log::error!("{}", logf!("Failed to lock database!"));
+            return actix_web::HttpResponse::InternalServerError()
+                .body("Database is in a deadlock. (DEADLOCK REFERENCE 😳)")
+        }
+    };
+
+    let template = dbt::Promotion {
+        name: promotion_id.into_inner(),
+        ..Default::default()
+    };
+
+    match template.remove(&db_locked) {
+        Ok(()) => actix_web::HttpResponse::Ok()
+            .body("Successfully removed the promotion."),
+        Err(err) => {
+            log::error!("{}: {}", logf!("Failed to remove value from database!"), err);
+            actix_web::HttpResponse::InternalServerError()
+                .body("Database failed.")
+        }
+    }
+
+}
+
+#[get("/stock")]
+pub async fn handler_stock(
+    db: web::Data<Arc<Mutex<sled::Db>>>
+) -> impl Responder { 
+
+    log::info!("{}", logf!("Entered."));
+
+    let db_locked = match db.lock() {
+        Ok(lock) => lock,
+        Err(_) => {
+            log::error!("{}", logf!("Failed to lock database!"));
+            return actix_web::HttpResponse::InternalServerError()
+                .body("Database is in a deadlock. (DEADLOCK REFERENCE 😳)")
+        }
+    };
+
+    match dbt::StockItem::get_all(&db_locked) {
+        Ok(stock) => actix_web::HttpResponse::Ok()
+            .json(req::StockResponseData {stock}),
+        Err(err) => {
+            log::error!("{}: {}", logf!("Failed to read value from database!"), err);
+            actix_web::HttpResponse::InternalServerError()
+                .body("Database failed.")
+        }
+    }
+
+}
+
+#[post("/stock")]
+pub async fn handler_stock_insert(
+    request_data: web::Json<req::StockInsertRequestData>,
+    db: web::Data<Arc<Mutex<sled::Db>>>
+) -> impl Responder { 
+
+    log::info!("{}", logf!("Entered."));
+
+    let db_locked = match db.lock() {
+        Ok(lock) => lock,
+        Err(_) => {
+            log::error!("{}", logf!("Failed to lock database!"));
+            return actix_web::HttpResponse::InternalServerError()
+                .body("Database is in a deadlock. (DEADLOCK REFERENCE 😳)")
+        }
+    };
+
+    let item = request_data.into_inner().item;
+
+    if item.name.trim().is_empty() {
+        return actix_web::HttpResponse::BadRequest()
+            .body("Stock item needs a name.")
+    }
+
+    match item.insert(&db_locked).and_then(|()| inventory::refresh_offers(&db_locked)) {
+        Ok(()) => actix_web::HttpResponse::Ok()
+            .body("Successfully created the stock item."),
+        Err(err) => {
+            log::error!("{}: {}", logf!("Failed to write value to database!"), err);
+            actix_web::HttpResponse::InternalServerError()
+                .body("Database failed.")
+        }
+    }
+
+}
+
+#[delete("/stock/{id}")]
+pub async fn handler_stock_delete(
+    stock_id: web::Path<dbt::StockItemID>,
+    db: web::Data<Arc<Mutex<sled::Db>>>
+) -> impl Responder { 
+
+    log::info!("{}", logf!("Entered."));
+
+    let db_locked = match db.lock() {
+        Ok(lock) => lock,
+        Err(_) => {
+            log::error!("{}", logf!("Failed to lock database!"));
+            return actix_web::HttpResponse::InternalServerError()
+                .body("Database is in a deadlock. (DEADLOCK REFERENCE 😳)")
+        }
+    };
+
+    let stock_id = stock_id.into_inner();
+
+    match dbt::Offer::get_all(&db_locked) {
+        Ok(offers) => {
+            if let Some(offer) = offers.iter().find(|offer| {
+                offer.recipe.iter().any(|ingredient| ingredient.stock == stock_id)
+            }) {
+                return actix_web::HttpResponse::BadRequest()
+                    .body(format!("Stock item is still used by `{}`.", offer.name))
+            }
+        }
+        Err(err) => {
+            log::error!("{}: {}", logf!("Failed to read offers!"), err);
+            return actix_web::HttpResponse::InternalServerError()
+                .body("Database failed.")
+        }
+    }
+
+    let template = dbt::StockItem {
+        name: stock_id,
+        ..Default::default()
+    };
+
+    match template.remove(&db_locked) {
+        Ok(()) => actix_web::HttpResponse::Ok()
+            .body("Successfully removed the stock item."),
+        Err(err) => {
+            log::error!("{}: {}", logf!("Failed to remove value from database!"), err);
+            actix_web::HttpResponse::InternalServerError()
+                .body("Database failed.")
+        }
+    }
+
+}
+
+fn stock_change_response(
+    result: Result<Option<dbt::StockItem>, String>
+) -> actix_web::HttpResponse {
+
+    match result {
+        Ok(Some(item)) => actix_web::HttpResponse::Ok()
+            .json(req::StockChangeResponseData {item}),
+        Ok(None) => actix_web::HttpResponse::NotFound()
+            .body("Stock item does not exist!"),
+        Err(err) => {
+            log::error!("{}: {}", logf!("Failed to update stock!"), err);
+            actix_web::HttpResponse::InternalServerError()
+                .body("Database failed.")
+        }
+    }
+
+}
+
+#[post("/stock-receive")]
+pub async fn handler_stock_receive(
+    request_data: web::Json<req::StockReceiveRequestData>,
+    db: web::Data<Arc<Mutex<sled::Db>>>
+) -> impl Responder { 
+
+    log::info!("{}", logf!("Entered."));
+
+    let db_locked = match db.lock() {
+        Ok(lock) => lock,
+        Err(_) => {
+            log::error!("{}", logf!("Failed to lock database!"));
+            return actix_web::HttpResponse::InternalServerError()
+                .body("Database is in a deadlock. (DEADLOCK REFERENCE 😳)")
+        }
+    };
+
+    let data = request_data.into_inner();
+
+    if data.quantity <= 0 {
+        return actix_web::HttpResponse::BadRequest()
+            .body("Received quantity has to be positive.")
+    }
+
+    stock_change_response(
+        inventory::update(&data.stock, |item| item.quantity += data.quantity, &db_locked)
+    )
+
+}
+
+#[post("/stock-adjust")]
+pub async fn handler_stock_adjust(
+    request_data: web::Json<req::StockAdjustRequestData>,
+    db: web::Data<Arc<Mutex<sled::Db>>>
+) -> impl Responder { 
+
+    log::info!("{}", logf!("Entered."));
+
+    let db_locked = match db.lock() {
+        Ok(lock) => lock,
+        Err(_) => {
+            log::error!("{}", logf!("Failed to lock database!"));
+            return actix_web::HttpResponse::InternalServerError()
+                .body("Database is in a deadlock. (DEADLOCK REFERENCE 😳)")
+        }
+    };
+
+    let data = request_data.into_inner();
+
+    log::info!(
+        "{}",
+        logf!(format!(
+            "Adjusting `{}`: {}",
+            data.stock,
+            data.reason.as_deref().unwrap_or("no reason given")
+        ))
+    );
+
+    stock_change_response(
+        inventory::update(
+            &data.stock,
+            |item| match data.counted {
+                Some(counted) => item.quantity = counted,
+                None => item.quantity += data.quantity
+            },
+            &db_locked
+        )
+    )
+
+}
+
+#[get("/tax-rates")]
+pub async fn handler_tax_rates(
+    db: web::Data<Arc<Mutex<sled::Db>>>
+) -> impl Responder { 
+
+    log::info!("{}", logf!("Entered."));
+
+    let db_locked = match db.lock() {
+        Ok(lock) => lock,
+        Err(_) => {
+            log::error!("{}", logf!("Failed to lock database!"));
+            return actix_web::HttpResponse::InternalServerError()
+                .body("Database is in a deadlock. (DEADLOCK REFERENCE 😳)")
+        }
+    };
+
+    match dbt::TaxRate::get_all(&db_locked) {
+        Ok(tax_rates) => actix_web::HttpResponse::Ok()
+            .json(req::TaxRatesResponseData {tax_rates}),
+        Err(err) => {
+            log::error!("{}: {}", logf!("Failed to read value from database!"), err);
+            actix_web::HttpResponse::InternalServerError()
+                .body("Database failed.")
+        }
+    }
+
+}
+
+#[post("/tax-rates")]
+pub async fn handler_tax_rates_insert(
+    request_data: web::Json<req::TaxRatesInsertRequestData>,
+    db: web::Data<Arc<Mutex<sled::Db>>>
+) -> impl Responder { 
+
+    log::info!("{}", logf!("Entered."));
+
+    let db_locked = match db.lock() {
+        Ok(lock) => lock,
+        Err(_) => {
+            log::error!("{}", logf!("Failed to lock database!"));
+            return actix_web::HttpResponse::InternalServerError()
+                .body("Database is in a deadlock. (DEADLOCK REFERENCE 😳)")
+        }
+    };
+
+    match request_data.into_inner().tax_rate.insert(&db_locked) {
+        Ok(()) => actix_web::HttpResponse::Ok()
+            .body("Successfully created the tax rate."),
+        Err(err) => {
+            log::error!("{}: {}", logf!("Failed to write value to database!"), err);
+            actix_web::HttpResponse::InternalServerError()
+                .body("Database failed.")
+        }
+    }
+
+}
+
+#[delete("/tax-rates/{id}")]
+pub async fn handler_tax_rates_delete(
+    tax_rate_id: web::Path<dbt::TaxRateID>,
+    db: web::Data<Arc<Mutex<sled::Db>>>
+) -> impl Responder { 
+
+    log::info!("{}", logf!("Entered."));
+
+    let db_locked = match db.lock() {
+        Ok(lock) => lock,
+        Err(_) => {
+            log::error!("{}", logf!("Failed to lock database!"));
+            return actix_web::HttpResponse::InternalServerError()
+                .body("Database is in a deadlock. (DEADLOCK REFERENCE 😳)")
+        }
+    };
+
+    let template = dbt::TaxRate {
+        name: tax_rate_id.into_inner(),
+        ..Default::default()
+    };
+
+    match template.remove(&db_locked) {
+        Ok(()) => actix_web::HttpResponse::Ok()
+            .body("Successfully removed the tax rate."),
+        Err(err) => {
+            log::error!("{}: {}", logf!("Failed to remove value from database!"), err);
+            actix_web::HttpResponse::InternalServerError()
+                .body("Database failed.")
+        }
+    }
+
+}
+
+#[post("/offers-sold-out")]
+pub async fn handler_offers_sold_out(
+    data: web::Json<req::OffersSoldOutRequestData>,
+    db: web::Data<Arc<Mutex<sled::Db>>>
+) -> impl Responder { 
+
+    log::info!("{}", logf!("Entered."));
+
+    let db_locked = match db.lock() {
+        Ok(lock) => lock,
+        Err(_) => {
+            log::error!("{}", logf!("Failed to lock database!"));
+            return actix_web::HttpResponse::InternalServerError()
+                .body("Database is in a deadlock. (DEADLOCK REFERENCE 😳)")
+        }
+    };
+
+    let data = data.into_inner();
+
+    let mut offer = match dbt::Offer::get(
+        dbt::Offer {
+            name: data.offer,
+            ..Default::default()
+        }.qualified_identifier(),
+        &db_locked
+    ) {
+        Ok(Some(offer)) => offer,
+        Ok(None) => {
+            log::error!("{}", logf!("No offer found!"));
+            return actix_web::HttpResponse::NotFound()
+                .body("Offer doesn't exist.")
+        }
+        Err(err) => {
+            log::error!("{}: {}", logf!("Failed to read value from database!"), err);
+            return actix_web::HttpResponse::InternalServerError()
+                .body("Database failed.")
+        }
+    };
+
+    offer.sold_out = data.sold_out;
+
+    match offer.insert(&db_locked) {
+        Ok(()) => actix_web::HttpResponse::Ok()
+            .json(req::OffersSoldOutResponseData {offer}),
+        Err(err) => {
+            log::error!("{}: {}", logf!("Failed to write value to database!"), err);
+            actix_web::HttpResponse::InternalServerError()
+                .body("Database failed.")
+        }
+    }
+
+}
+
+/// Parses a comma separated list of enum values.
+fn parse_list<T: std::str::FromStr>(list: &str) -> Result<Vec<T>, String> {
+    list.split(',')
+        .map(str::trim)
+        .filter(|item| !item.is_empty())
+        .map(|item| item.parse::<T>().map_err(|_| format!("Unknown value `{}`.", item)))
+        .collect()
+}
+
+/// Offers available right now, in the language asked for by `lang`
+/// or else by the `Accept-Language` header.
+#[get("/menu")]
+pub async fn handler_menu(
+    request: actix_web::HttpRequest,
+    filter: web::Query<req::MenuRequestData>,
+    db: web::Data<Arc<Mutex<sled::Db>>>
+) -> impl Responder { 
+
+    log::info!("{}", logf!("Entered."));
+
+    let (diet, exclude) = match (
+        parse_list::<dbt::DietaryTag>(&filter.diet),
+        parse_list::<dbt::Allergen>(&filter.exclude)
+    ) {
+        (Ok(diet), Ok(exclude)) => (diet, exclude),
+        (Err(err), _) | (_, Err(err)) => {
+            return actix_web::HttpResponse::BadRequest()
+                .body(err)
+        }
+    };
+
+    let db_locked = match db.lock() {
+        Ok(lock) => lock,
+        Err(err) => {
+            log::error!("{}: {}", logf!("Failed to lock database!"), err);
+            return actix_web::HttpResponse::InternalServerError()
+                .body("Database is in a deadlock. (DEADLOCK REFERENCE 😳)")
+        }
+    };
+
+    let (day, time) = clock::now();
+
+    let offers = match dbt::Offer::get_all(&db_locked) {
+        Ok(offers) => offers
+            .into_iter()
+            .filter(|offer| offer.is_available_at(day, time) && offer.suits(&diet, &exclude))
+            .collect::<Vec<dbt::Offer>>(),
+        Err(err) => {
+            log::error!("{}: {}", logf!("No offers found!"), err);
+            return actix_web::HttpResponse::InternalServerError()
+                .body("Failed to get offers due to a database error.")
+        } 
+    };
+
+    let (categories, venue) = match dbt::Category::get_all(&db_locked)
+        .and_then(|categories| Ok((
+            categories,
+            dbt::Venue::get(dbt::Venue::default().qualified_identifier(), &db_locked)?.unwrap_or_default()
+        )))
+    {
+        Ok(read) => read,
+        Err(err) => {
+            log::error!("{}: {}", logf!("Failed to read value from database!"), err);
+            return actix_web::HttpResponse::InternalServerError()
+                .body("Database failed.")
+        }
+    };
+
+    let base = venue.language.to_lowercase();
+    let languages = i18n::available(&base, &offers, &categories);
+    let language = i18n::negotiate(
+        &i18n::requested(
+            filter.lang.as_deref(),
+            request.headers()
+                .get(actix_web::http::header::ACCEPT_LANGUAGE)
+                .and_then(|header| header.to_str().ok())
+        ),
+        &languages,
+        &base
+    );
+
+    actix_web::HttpResponse::Ok()
+        .json(req::MenuResponseData {
+            offers: offers
+                .into_iter()
+                .map(|offer| i18n::localize(offer, &categories, &language))
+                .collect(),
+            language,
+            languages
+        })
+
+}
+
+#[get("/orders")]
+pub async fn handler_orders(
+    data: web::Json<req::OrdersRequestData>,
+    db: web::Data<Arc<Mutex<sled::Db>>>
+) -> impl Responder { 
+
+    log::info!("{}", logf!("Entered."));
+
+    let db_locked = match db.lock() {
+        Ok(lock) => lock,
+        Err(err) => {
+            log::error!("{}: {}", logf!("Failed to lock database!"), err);
+            return actix_web::HttpResponse::InternalServerError()
+                .body("Database is in a deadlock. (DEADLOCK REFERENCE 😳)")
+        }
+    };
+
+    
+    let probable_orders = if !data.new && data.table.is_none() {
+        dbt::Order::get_all(&*db_locked)
+    } else {
+        if data.table.is_some() {
+
+            dbt::Order {
+                id: dbt::OrderID {
+                    count: 0,
+                    table: data.table.clone().unwrap()
+                },
+                finished: !data.new,
+                ..Default::default()
+            }.get_templated(&*db_locked) 
+
+        } else {
+
+            dbt::Order {
+                id: dbt::OrderID {
+                    count: 0,
+                    table: "".to_string()
+                },
+                finished: !data.new,
+                ..Default::default()
+            }.get_status(&*db_locked) 
+            
+        }
+    };
+
+    let mut orders = match probable_orders {
+        Ok(orders) => orders,
+        Err(err) => {
+            log::error!("{}: {}", logf!("No offers found!"), err);
+            return actix_web::HttpResponse::InternalServerError()
+                .body("Failed to get offers due to a database error.")
+        } 
+    };
+
+    if data.current {
+        match session::open_sessions(&db_locked) {
+            Ok(open) => orders.retain(|order| {
+                order.session.is_some_and(|session| open.contains(&session))
+            }),
+            Err(err) => {
+                log::error!("{}: {}", logf!("Failed to read sessions!"), err);
+                return actix_web::HttpResponse::InternalServerError()
+                    .body("Database failed.")
+            }
+        }
+    }
+
+
+    let tables = totals::table_totals(&orders);
+
+    return actix_web::HttpResponse::Ok()
+        .json(req::OrdersResponseData {
+            orders: orders.into_iter().map(req::TotaledOrder::from).collect(),
+            tables
+        })
+
+}
+
+#[get("/orders/specific")]
+pub async fn handler_orders_specific(
+    data: web::Json<req::OrdersSpecificRequestData>,
+    db: web::Data<Arc<Mutex<sled::Db>>>
+) -> impl Responder { 
+
+    log::info!("{}", logf!("Entered."));
+
+    let db_locked = match db.lock() {
+        Ok(lock) => lock,
+        Err(err) => {
+            log::error!("{}: {}", logf!("Failed to lock database!"), err);
+            return actix_web::HttpResponse::InternalServerError()
+                .reason("Database is in a deadlock. (DEADLOCK REFERENCE 😳)")
+                .body("")
+        }
+    };
+
+    match dbt::Order::get(
+        data.into_inner().order.qualified_identifier(), 
+        &*db_locked
+    ) {
+        Ok(Some(order)) => {
+            return actix_web::HttpResponse::Ok()
+                .json(req::OrdersSpecificResponseData {order: order.into()})
+        }
+        Ok(None) => {
+            log::error!("{}", logf!("No offer found!"));
+            return actix_web::HttpResponse::NotFound()
+                .reason("Does not exist!")
+                .body("")
+        }
+        Err(err) => {
+            log::error!("{}: {}", logf!("No offers found!"), err);
+            return actix_web::HttpResponse::InternalServerError()
+                .reason("Failed to get offers due to a database error.")
+                .body("")
+        } 
+    }
+
+}
+
+
+
+#[post("/orders")]
+pub async fn handler_orders_insert(
+    data: web::Json<req::OrdersInsertRequestData>,
+    authenticated: Option<web::ReqData<auth::Authenticated>>,
+    db: web::Data<Arc<Mutex<sled::Db>>>
+) -> impl Responder { 
+
+    log::info!("{}", logf!("Entered."));
+
+    let db_locked = match db.lock() {
+        Ok(lock) => lock,
+        Err(_) => {
+            log::error!("{}", logf!("Failed to lock database!"));
+            return actix_web::HttpResponse::InternalServerError()
+                .body("Database is in a deadlock. (DEADLOCK REFERENCE 😳)")
+        }
+    };
+
+    let data = data.into_inner();
+    let mut template = data.order;
+
+    let mut table = match dbt::VirtualTable::get(
+        dbt::VirtualTable {
+            name: template.clone().id.table,
+            ..Default::default()
+        }.qualified_identifier(), 
+        &db_locked
+    ) {
+        Ok(Some(table)) => table,
+        Ok(None) => {
+            log::error!("{}", logf!("Order for a table that doesn't exist!"));
+            return actix_web::HttpResponse::BadRequest()
+                .json(req::OrdersInsertErrorData {
+                    errors: vec![req::OrderLineError {
+                        line: None,
+                        offer: None,
+                        problem: req::OrderProblem::UnknownTable
+                    }]
+                })
+        }
+        Err(_) => {
+            return actix_web::HttpResponse::InternalServerError()
+                .body("Database failed.")
+        }
+    };
+
+    // Guests can only order for the table of their link, staff taking
+    // orders for any.
+    let by_staff = authenticated
+        .filter(|authenticated| {
+            auth::has_permission(&authenticated.staff, dbt::Permission::TakeOrders)
+        })
+        .map(|authenticated| authenticated.into_inner().staff.name);
+
+    // Guest orders wait for a waiter to accept them, and can't use
+    // the codes of staff promotions.
+    template.accepted_by = by_staff.clone();
+    template.served_by = None;
+    let by_staff = by_staff.is_some();
+    if !by_staff {
+        template.codes.clear();
+    }
+
+    if !by_staff {
+        let authorized = match guest::signing_key(&db_locked) {
+            Ok(key) => data.token.is_some_and(|token| guest::verify(&token, &table, &key)),
+            Err(err) => {
+                log::error!("{}: {}", logf!("Failed to read signing key!"), err);
+                return actix_web::HttpResponse::InternalServerError()
+                    .body("Database failed.")
+            }
+        };
+        if !authorized {
+            log::info!("{}", logf!(format!("Rejected guest order for `{}` without a valid token.", table.name)));
+            return actix_web::HttpResponse::Forbidden()
+                .body("This link is not valid for the table, scan the code on the table again.")
+        }
+    }
+
+    let offers = match dbt::Offer::get_all(&db_locked) {
+        Ok(offers) => offers,
+        Err(err) => {
+            log::error!("{}: {}", logf!("Failed to read offers!"), err);
+            return actix_web::HttpResponse::InternalServerError()
+                .body("Database failed.")
+        }
+    };
+
+    if let Err(errors) = validation::validate_order(&mut template, &offers, clock::now()) {
+        log::error!("{}", logf!(format!("Rejected order with {} problem(s).", errors.errors.len())));
+        return actix_web::HttpResponse::BadRequest()
+            .json(errors)
+    }
+
+    let stock = match dbt::StockItem::get_all(&db_locked) {
+        Ok(stock) => stock,
+        Err(err) => {
+            log::error!("{}: {}", logf!("Failed to read stock!"), err);
+            return actix_web::HttpResponse::InternalServerError()
+                .body("Database failed.")
+        }
+    };
+
+    let errors = inventory::check(&template, &offers, &stock);
+    if !errors.is_empty() {
+        log::error!("{}", logf!("Rejected order, not enough stock."));
+        return actix_web::HttpResponse::BadRequest()
+            .json(req::OrdersInsertErrorData { errors })
+    }
+
+    let categories = match dbt::Category::get_all(&db_locked) {
+        Ok(categories) => categories,
+        Err(err) => {
+            log::error!("{}: {}", logf!("Failed to read categories!"), err);
+            return actix_web::HttpResponse::InternalServerError()
+                .body("Database failed.")
+        }
+    };
+
+    let tax_rates = match dbt::TaxRate::get_all(&db_locked) {
+        Ok(tax_rates) => tax_rates,
+        Err(err) => {
+            log::error!("{}: {}", logf!("Failed to read tax rates!"), err);
+            return actix_web::HttpResponse::InternalServerError()
+                .body("Database failed.")
+        }
+    };
+
+    let promotion_list = match dbt::Promotion::get_all(&db_locked) {
+        Ok(promotion_list) => promotion_list,
+        Err(err) => {
+            log::error!("{}: {}", logf!("Failed to read promotions!"), err);
+            return actix_web::HttpResponse::InternalServerError()
+                .body("Database failed.")
+        }
+    };
+
+    validation::snapshot_offers(&mut template, &offers, &categories, &tax_rates);
+    promotions::apply(&mut template, &promotion_list, &offers, clock::now());
+
+    table.order_count = table.order_count+1;
+    if table.state == dbt::TableState::Free {
+        table.state = dbt::TableState::Occupied;
+    }
+    template.id.count = table.order_count;
+    template.finished = false;
+    template.paid = false;
+
+    template.session = match session::session_for_order(&table.name, &db_locked) {
+        Ok(session) => Some(session.id),
+        Err(err) => {
+            log::error!("{}: {}", logf!("Failed to open a session!"), err);
+            return actix_web::HttpResponse::InternalServerError()
+                .body("Database failed.")
+        }
+    };
+
+    // The table and its order are stored together, and the stock is
+    // only taken once the order exists.
+    let mut batch = sled::Batch::default();
+    let stored = table.insert_batched(&mut batch)
+        .and_then(|()| template.insert_batched(&mut batch))
+        .and_then(|()| db_locked.apply_batch(batch).map_err(|err| err.to_string()))
+        .and_then(|()| tickets::route(&template, &offers, &categories, &db_locked));
+
+    if let Err(err) = stored {
+        log::error!("{}: {}", logf!("Failed to insert value into database!"), err);
+        return actix_web::HttpResponse::InternalServerError()
+            .body("Database failed.")
+    }
+
+    match inventory::consume(&template, &offers, &db_locked) {
+        Ok(()) => actix_web::HttpResponse::Ok()
+            .json(req::OrdersInsertResponseData { order: template.id }),
+        Err(err) => {
+            log::error!("{}: {}", logf!("Failed to update stock!"), err);
+            actix_web::HttpResponse::InternalServerError()
+                .body("Database failed.")
+        }
+    }
+
+}
+
+
+#[delete("/orders")]
+pub async fn handler_orders_delete(
+    data: web::Json<req::OrdersDeleteRequestData>,
+    db: web::Data<Arc<Mutex<sled::Db>>>
+) -> impl Responder { 
+
+    log::info!("{}", logf!("Entered."));
+
+    let db_locked = match db.lock() {
+        Ok(lock) => lock,
+        Err(_) => {
+            log::error!("{}", logf!("Failed to lock database!"));
+            return actix_web::HttpResponse::InternalServerError()
+                .body("Database is in a deadlock. (DEADLOCK REFERENCE 😳)")
+        }
+    };
+
+    let order = data.into_inner().order;
+
+    // The stored order is removed, the flags sent with the request
+    // could point at a key the order isn't kept under.
+    let stored = match db::order_by_id(&order.id, &db_locked) {
+        Ok(Some(stored)) => stored,
+        Ok(None) => {
+            return actix_web::HttpResponse::NotFound()
+                .body("Order does not exist!")
+        }
+        Err(err) => {
+            log::error!("{}: {}", logf!("Failed to read value from database!"), err);
+            return actix_web::HttpResponse::InternalServerError()
+                .body("Database failed.")
+        }
+    };
+
+    // Bills and receipts keep pointing at their orders.
+    if stored.paid {
+        return actix_web::HttpResponse::Conflict()
+            .body("Order is already paid.")
+    }
+
+    let on_open_bill = dbt::Bill {
+        closed: false,
+        ..Default::default()
+    }
+    .get_status(&db_locked)
+    .map(|bills| bills.into_iter().find(|bill| bill.orders.contains(&stored.id)));
+
+    match on_open_bill {
+        Ok(None) => (),
+        Ok(Some(bill)) => {
+            return actix_web::HttpResponse::Conflict()
+                .body(format!("Order is on the open bill {}.", bill.id))
+        }
+        Err(err) => {
+            log::error!("{}: {}", logf!("Failed to read value from database!"), err);
+            return actix_web::HttpResponse::InternalServerError()
+                .body("Database failed.")
+        }
+    }
+
+    if let Err(err) = stored.remove(&db_locked).and_then(|()| tickets::remove_of_order(&stored.id, &db_locked)) {
+        log::error!("{}: {}", logf!("Failed to remove value from database!"), err);
+        return actix_web::HttpResponse::InternalServerError()
+            .body("Database failed.")
+    }
+
+    // An order cancelled before it was made gives back its stock.
+    if !stored.finished {
+        let restocked = dbt::Offer::get_all(&db_locked)
+            .and_then(|offers| inventory::restock(&stored, &offers, &db_locked));
+        if let Err(err) = restocked {
+            log::error!("{}: {}", logf!("Failed to update stock!"), err);
+            return actix_web::HttpResponse::InternalServerError()
+                .body("Database failed.")
+        }
+    }
+
+    actix_web::HttpResponse::Ok()
+        .body("Successfully removed the order.")
+
+}
+
+#[post("/orders-accept")]
+pub async fn handler_orders_accept(
+    data: web::Json<req::OrdersAcceptRequestData>,
+    authenticated: web::ReqData<auth::Authenticated>,
+    db: web::Data<Arc<Mutex<sled::Db>>>
+) -> impl Responder {
+
+    log::info!("{}", logf!("Entered."));
+
+    let db_locked = match db.lock() {
+        Ok(lock) => lock,
+        Err(_) => {
+            log::error!("{}", logf!("Failed to lock database!"));
+            return actix_web::HttpResponse::InternalServerError()
+                .body("Database is in a deadlock. (DEADLOCK REFERENCE 😳)")
+        }
+    };
+
+    let staff = authenticated.into_inner().staff.name;
+
+    let mut order = match db::order_by_id(&data.into_inner().order, &db_locked) {
+        Ok(Some(order)) => order,
+        Ok(None) => {
+            return actix_web::HttpResponse::NotFound()
+                .body("Order doesn't exist.")
+        }
+        Err(err) => {
+            log::error!("{}: {}", logf!("Failed to read value from database!"), err);
+            return actix_web::HttpResponse::InternalServerError()
+                .body("Database failed.")
+        }
+    };
+
+    match &order.accepted_by {
+        Some(accepted_by) if accepted_by != &staff => {
+            return actix_web::HttpResponse::Conflict()
+                .body(format!("Order was already accepted by `{}`.", accepted_by))
+        }
+        _ => ()
+    }
+
+    order.accepted_by = Some(staff);
+
+    match order.insert(&db_locked) {
+        Ok(()) => actix_web::HttpResponse::Ok()
+            .json(req::TotaledOrder::from(order)),
+        Err(err) => {
+            log::error!("{}: {}", logf!("Failed to write value to database!"), err);
+            actix_web::HttpResponse::InternalServerError()
+                .body("Database failed.")
+        }
+    }
+
+}
+
+#[post("/orders-finish")]
+pub async fn handler_orders_finish(
+    data: web::Json<req::OrdersDeleteRequestData>,
+    authenticated: web::ReqData<auth::Authenticated>,
+    db: web::Data<Arc<Mutex<sled::Db>>>
+) -> impl Responder { 
+
+    log::info!("{}", logf!("Entered."));
+
+    let db_locked = match db.lock() {
+        Ok(lock) => lock,
+        Err(_) => {
+            log::error!("{}", logf!("Failed to lock database!"));
+            return actix_web::HttpResponse::InternalServerError()
+                .body("Database is in a deadlock. (DEADLOCK REFERENCE 😳)")
+        }
+    };
+
+    let template = data.into_inner().order;
+
+    let order = match dbt::Order::get(
+        template.qualified_identifier(),
+        &db_locked
+    ) {
+        Ok(Some(mut order)) => {
+            order.finished = true;
+            order.served_by = Some(authenticated.into_inner().staff.name);
+            order
+        },
+        Ok(None) | Err(_) => {
+            return actix_web::HttpResponse::InternalServerError()
+                .body("Order doesn't exist.")
+        }
+    };
+
+    match template.remove(&db_locked){
+        Ok(()) => (),
+        Err(err) => {
+            log::error!("{}: {}", logf!("Failed to lock database!"), err);
+            return actix_web::HttpResponse::InternalServerError()
+                .body("Database failed.")
+        }
+    }
+
+    match order.insert(&db_locked).and_then(|()| tickets::serve_order(&order.id, &db_locked)) {
         Ok(()) => {
             return actix_web::HttpResponse::Ok()
                 .json(req::OrdersFinishResponseData {
@@ -555,14 +2000,1620 @@ pub async fn handler_orders_finish(
                 })
         }
         Err(err) => {
-            log::error!("{}: {}", logf!("Failed to lock database!"), err);
+            log::error!("{}: {}", logf!("Failed to lock database!"), err);
+            return actix_web::HttpResponse::InternalServerError()
+                .body("Database failed.")
+        }
+    }
+
+}
+
+
+/// `BadRequest` if the station doesn't exist.
+fn check_station(station: &Option<dbt::StationID>, db: &sled::Db) -> Option<actix_web::HttpResponse> {
+
+    let station = station.as_ref()?;
+
+    match (dbt::Station {name: station.clone()}).exists(db) {
+        Ok(true) => None,
+        Ok(false) => Some(
+            actix_web::HttpResponse::BadRequest()
+                .body("Station does not exist!")
+        ),
+        Err(err) => {
+            log::error!("{}: {}", logf!("Failed to read value from database!"), err);
+            Some(
+                actix_web::HttpResponse::InternalServerError()
+                    .body("Database failed.")
+            )
+        }
+    }
+
+}
+
+fn billing_error_response(err: billing::BillingError) -> actix_web::HttpResponse {
+    match err {
+        billing::BillingError::NotFound(reason) => {
+            log::info!("{}: {}", logf!("Nothing found"), reason);
+            actix_web::HttpResponse::NotFound()
+                .body(reason)
+        }
+        billing::BillingError::Invalid(reason) => {
+            log::info!("{}: {}", logf!("Rejected"), reason);
+            actix_web::HttpResponse::BadRequest()
+                .body(reason)
+        }
+        billing::BillingError::Database(err) => {
+            log::error!("{}: {}", logf!("Database failed!"), err);
+            actix_web::HttpResponse::InternalServerError()
+                .body("Database failed.")
+        }
+    }
+}
+
+fn moved_response(
+    moved: Result<Vec<req::MovedOrder>, billing::BillingError>
+) -> actix_web::HttpResponse {
+
+    match moved {
+        Ok(moved) => actix_web::HttpResponse::Ok()
+            .json(req::OrdersMoveResponseData {moved}),
+        Err(err) => billing_error_response(err)
+    }
+
+}
+
+#[post("/orders-move")]
+pub async fn handler_orders_move(
+    request_data: web::Json<req::OrdersMoveRequestData>,
+    db: web::Data<Arc<Mutex<sled::Db>>>
+) -> impl Responder { 
+
+    log::info!("{}", logf!("Entered."));
+
+    let db_locked = match db.lock() {
+        Ok(lock) => lock,
+        Err(_) => {
+            log::error!("{}", logf!("Failed to lock database!"));
+            return actix_web::HttpResponse::InternalServerError()
+                .body("Database is in a deadlock. (DEADLOCK REFERENCE 😳)")
+        }
+    };
+
+    let data = request_data.into_inner();
+
+    let order = match db::order_by_id(&data.order, &db_locked) {
+        Ok(Some(order)) => order,
+        Ok(None) => return actix_web::HttpResponse::NotFound()
+            .body("Order doesn't exist."),
+        Err(err) => return billing_error_response(err.into())
+    };
+
+    moved_response(transfer::move_orders(vec![order], &data.to, &db_locked))
+
+}
+
+#[post("/sessions-move")]
+pub async fn handler_sessions_move(
+    request_data: web::Json<req::SessionsMoveRequestData>,
+    db: web::Data<Arc<Mutex<sled::Db>>>
+) -> impl Responder { 
+
+    log::info!("{}", logf!("Entered."));
+
+    let db_locked = match db.lock() {
+        Ok(lock) => lock,
+        Err(_) => {
+            log::error!("{}", logf!("Failed to lock database!"));
+            return actix_web::HttpResponse::InternalServerError()
+                .body("Database is in a deadlock. (DEADLOCK REFERENCE 😳)")
+        }
+    };
+
+    let data = request_data.into_inner();
+
+    let orders = match session::orders_of_session(data.session, &db_locked) {
+        Ok(orders) => orders.into_iter().filter(|order| !order.paid).collect(),
+        Err(err) => return billing_error_response(err.into())
+    };
+
+    moved_response(transfer::move_orders(orders, &data.to, &db_locked))
+
+}
+
+#[post("/tables-merge")]
+pub async fn handler_tables_merge(
+    request_data: web::Json<req::TablesMergeRequestData>,
+    db: web::Data<Arc<Mutex<sled::Db>>>
+) -> impl Responder { 
+
+    log::info!("{}", logf!("Entered."));
+
+    let db_locked = match db.lock() {
+        Ok(lock) => lock,
+        Err(_) => {
+            log::error!("{}", logf!("Failed to lock database!"));
+            return actix_web::HttpResponse::InternalServerError()
+                .body("Database is in a deadlock. (DEADLOCK REFERENCE 😳)")
+        }
+    };
+
+    let data = request_data.into_inner();
+
+    let orders = match transfer::open_orders_of_table(&data.from, &db_locked) {
+        Ok(orders) => orders,
+        Err(err) => return billing_error_response(err.into())
+    };
+
+    moved_response(transfer::move_orders(orders, &data.into, &db_locked))
+
+}
+
+#[get("/reservations-{date}")]
+pub async fn handler_reservations(
+    date: web::Path<String>,
+    db: web::Data<Arc<Mutex<sled::Db>>>
+) -> impl Responder { 
+
+    log::info!("{}", logf!("Entered."));
+
+    let db_locked = match db.lock() {
+        Ok(lock) => lock,
+        Err(_) => {
+            log::error!("{}", logf!("Failed to lock database!"));
+            return actix_web::HttpResponse::InternalServerError()
+                .body("Database is in a deadlock. (DEADLOCK REFERENCE 😳)")
+        }
+    };
+
+    let date = match reservations::parse_date(&date.into_inner()) {
+        Some(date) => date,
+        None => {
+            return actix_web::HttpResponse::BadRequest()
+                .body("Date has to be in the `YYYY-MM-DD` format.")
+        }
+    };
+
+    match reservations::reservations_of_day(date, &db_locked) {
+        Ok(reservations) => actix_web::HttpResponse::Ok()
+            .json(req::ReservationsResponseData {
+                date: date.format("%Y-%m-%d").to_string(),
+                reservations
+            }),
+        Err(err) => {
+            log::error!("{}: {}", logf!("Failed to read value from database!"), err);
+            actix_web::HttpResponse::InternalServerError()
+                .body("Database failed.")
+        }
+    }
+
+}
+
+#[post("/reservations")]
+pub async fn handler_reservations_insert(
+    request_data: web::Json<req::ReservationsInsertRequestData>,
+    db: web::Data<Arc<Mutex<sled::Db>>>
+) -> impl Responder { 
+
+    log::info!("{}", logf!("Entered."));
+
+    let db_locked = match db.lock() {
+        Ok(lock) => lock,
+        Err(_) => {
+            log::error!("{}", logf!("Failed to lock database!"));
+            return actix_web::HttpResponse::InternalServerError()
+                .body("Database is in a deadlock. (DEADLOCK REFERENCE 😳)")
+        }
+    };
+
+    let mut reservation = request_data.into_inner().reservation;
+    reservation.status = dbt::ReservationStatus::Booked;
+    reservation.id = match db_locked.generate_id() {
+        Ok(id) => id,
+        Err(err) => {
+            log::error!("{}: {}", logf!("Failed to generate an id!"), err);
+            return actix_web::HttpResponse::InternalServerError()
+                .body("Database failed.")
+        }
+    };
+
+    match reservations::conflicts(&reservation, &db_locked) {
+        Ok(conflicts) if conflicts.is_empty() => (),
+        Ok(conflicts) => {
+            return actix_web::HttpResponse::Conflict()
+                .json(req::ReservationsConflictData {conflicts})
+        }
+        Err(err) => {
+            log::error!("{}: {}", logf!("Failed to read value from database!"), err);
+            return actix_web::HttpResponse::InternalServerError()
+                .body("Database failed.")
+        }
+    }
+
+    match reservation.insert(&db_locked) {
+        Ok(()) => actix_web::HttpResponse::Ok()
+            .json(req::ReservationsInsertResponseData {reservation}),
+        Err(err) => {
+            log::error!("{}: {}", logf!("Failed to write value to database!"), err);
+            actix_web::HttpResponse::InternalServerError()
+                .body("Database failed.")
+        }
+    }
+
+}
+
+#[post("/reservations-status")]
+pub async fn handler_reservations_status(
+    request_data: web::Json<req::ReservationsStatusRequestData>,
+    db: web::Data<Arc<Mutex<sled::Db>>>
+) -> impl Responder { 
+
+    log::info!("{}", logf!("Entered."));
+
+    let db_locked = match db.lock() {
+        Ok(lock) => lock,
+        Err(_) => {
+            log::error!("{}", logf!("Failed to lock database!"));
+            return actix_web::HttpResponse::InternalServerError()
+                .body("Database is in a deadlock. (DEADLOCK REFERENCE 😳)")
+        }
+    };
+
+    let data = request_data.into_inner();
+
+    let mut reservation = match reservations::reservation_by_id(data.reservation, &db_locked) {
+        Ok(Some(reservation)) => reservation,
+        Ok(None) => {
+            return actix_web::HttpResponse::NotFound()
+                .body("Reservation does not exist!")
+        }
+        Err(err) => {
+            log::error!("{}: {}", logf!("Failed to read value from database!"), err);
+            return actix_web::HttpResponse::InternalServerError()
+                .body("Database failed.")
+        }
+    };
+
+    reservation.status = data.status;
+
+    // Guests that arrive for a booked table take it right away.
+    let seated = match (&reservation.table, reservation.status) {
+        (Some(table), dbt::ReservationStatus::Arrived) => {
+            db::set_table_state(table, dbt::TableState::Occupied, &db_locked).map(|_| ())
+        }
+        _ => Ok(())
+    };
+
+    match seated.and_then(|()| reservation.insert(&db_locked)) {
+        Ok(()) => actix_web::HttpResponse::Ok()
+            .body("Successfully updated the reservation."),
+        Err(err) => {
+            log::error!("{}: {}", logf!("Failed to write value to database!"), err);
+            actix_web::HttpResponse::InternalServerError()
+                .body("Database failed.")
+        }
+    }
+
+}
+
+#[get("/sessions")]
+pub async fn handler_sessions(
+    db: web::Data<Arc<Mutex<sled::Db>>>
+) -> impl Responder { 
+
+    log::info!("{}", logf!("Entered."));
+
+    let db_locked = match db.lock() {
+        Ok(lock) => lock,
+        Err(_) => {
+            log::error!("{}", logf!("Failed to lock database!"));
+            return actix_web::HttpResponse::InternalServerError()
+                .body("Database is in a deadlock. (DEADLOCK REFERENCE 😳)")
+        }
+    };
+
+    let template = dbt::Session {
+        closed: false,
+        ..Default::default()
+    };
+
+    match template.get_status(&db_locked) {
+        Ok(sessions) => actix_web::HttpResponse::Ok()
+            .json(req::SessionsResponseData {sessions}),
+        Err(err) => {
+            log::error!("{}: {}", logf!("Failed to read value from database!"), err);
+            actix_web::HttpResponse::InternalServerError()
+                .body("Database failed.")
+        }
+    }
+
+}
+
+fn session_response(
+    found: Result<Option<dbt::Session>, String>,
+    db: &sled::Db
+) -> actix_web::HttpResponse {
+
+    match found.and_then(|session| session.map(|session| session::summarize(session, db)).transpose()) {
+        Ok(Some(session)) => actix_web::HttpResponse::Ok()
+            .json(session),
+        Ok(None) => actix_web::HttpResponse::NotFound()
+            .body("No such session."),
+        Err(err) => {
+            log::error!("{}: {}", logf!("Failed to read value from database!"), err);
+            actix_web::HttpResponse::InternalServerError()
+                .body("Database failed.")
+        }
+    }
+
+}
+
+#[get("/sessions-{id}")]
+pub async fn handler_sessions_specific(
+    session_id: web::Path<dbt::SessionID>,
+    db: web::Data<Arc<Mutex<sled::Db>>>
+) -> impl Responder { 
+
+    log::info!("{}", logf!("Entered."));
+
+    let db_locked = match db.lock() {
+        Ok(lock) => lock,
+        Err(_) => {
+            log::error!("{}", logf!("Failed to lock database!"));
+            return actix_web::HttpResponse::InternalServerError()
+                .body("Database is in a deadlock. (DEADLOCK REFERENCE 😳)")
+        }
+    };
+
+    session_response(
+        session::session_by_id(session_id.into_inner(), &db_locked),
+        &db_locked
+    )
+
+}
+
+#[get("/tables-{id}/session")]
+pub async fn handler_tables_session(
+    table_id: web::Path<dbt::VirtualTableID>,
+    db: web::Data<Arc<Mutex<sled::Db>>>
+) -> impl Responder { 
+
+    log::info!("{}", logf!("Entered."));
+
+    let db_locked = match db.lock() {
+        Ok(lock) => lock,
+        Err(_) => {
+            log::error!("{}", logf!("Failed to lock database!"));
+            return actix_web::HttpResponse::InternalServerError()
+                .body("Database is in a deadlock. (DEADLOCK REFERENCE 😳)")
+        }
+    };
+
+    session_response(
+        session::open_session_of_table(&table_id.into_inner(), &db_locked),
+        &db_locked
+    )
+
+}
+
+/// Orders of the party at the table and how far along they are, for
+/// guests holding the link of the table.
+#[get("/tables-{id}/orders")]
+pub async fn handler_tables_orders(
+    table_id: web::Path<dbt::VirtualTableID>,
+    query: web::Query<req::GuestPageRequestData>,
+    db: web::Data<Arc<Mutex<sled::Db>>>
+) -> impl Responder {
+
+    log::info!("{}", logf!("Entered."));
+
+    let db_locked = match db.lock() {
+        Ok(lock) => lock,
+        Err(_) => {
+            log::error!("{}", logf!("Failed to lock database!"));
+            return actix_web::HttpResponse::InternalServerError()
+                .body("Database is in a deadlock. (DEADLOCK REFERENCE 😳)")
+        }
+    };
+
+    let table = match dbt::VirtualTable::get(
+        dbt::VirtualTable {
+            name: table_id.into_inner(),
+            ..Default::default()
+        }.qualified_identifier(),
+        &db_locked
+    ) {
+        Ok(Some(table)) => table,
+        Ok(None) => return actix_web::HttpResponse::NotFound()
+            .body("Table does not exist!"),
+        Err(err) => {
+            log::error!("{}: {}", logf!("Failed to read value from database!"), err);
+            return actix_web::HttpResponse::InternalServerError()
+                .body("Database failed.")
+        }
+    };
+
+    let authorized = match guest::signing_key(&db_locked) {
+        Ok(key) => query.into_inner().token.is_some_and(|token| guest::verify(&token, &table, &key)),
+        Err(err) => {
+            log::error!("{}: {}", logf!("Failed to read signing key!"), err);
+            return actix_web::HttpResponse::InternalServerError()
+                .body("Database failed.")
+        }
+    };
+    if !authorized {
+        return actix_web::HttpResponse::Forbidden()
+            .body("This link is not valid for the table, scan the code on the table again.")
+    }
+
+    let orders = session::open_session_of_table(&table.name, &db_locked)
+        .and_then(|session| match session {
+            Some(session) => session::orders_of_session(session.id, &db_locked),
+            None => Ok(Vec::new())
+        })
+        .and_then(|orders| Ok((
+            orders,
+            dbt::Ticket::get_all(&db_locked)?,
+            alerts::open_alerts(Some(&table.name), &db_locked)?
+        )));
+
+    match orders {
+        Ok((mut orders, ticket_list, alert_list)) => {
+            orders.sort_by_key(|order| order.id.count);
+            actix_web::HttpResponse::Ok()
+                .json(req::TablesOrdersResponseData {
+                    table: table.name,
+                    orders: orders
+                        .iter()
+                        .map(|order| tickets::guest_order(order, &ticket_list))
+                        .collect(),
+                    alerts: alert_list.into_iter().map(|alert| alert.kind).collect()
+                })
+        }
+        Err(err) => {
+            log::error!("{}: {}", logf!("Failed to read value from database!"), err);
+            actix_web::HttpResponse::InternalServerError()
+                .body("Database failed.")
+        }
+    }
+
+}
+
+#[get("/alerts")]
+pub async fn handler_alerts(
+    filter: web::Query<req::AlertsRequestData>,
+    authenticated: web::ReqData<auth::Authenticated>,
+    db: web::Data<Arc<Mutex<sled::Db>>>
+) -> impl Responder {
+
+    log::info!("{}", logf!("Entered."));
+
+    let db_locked = match db.lock() {
+        Ok(lock) => lock,
+        Err(_) => {
+            log::error!("{}", logf!("Failed to lock database!"));
+            return actix_web::HttpResponse::InternalServerError()
+                .body("Database is in a deadlock. (DEADLOCK REFERENCE 😳)")
+        }
+    };
+
+    let alert_list = alerts::open_alerts(None, &db_locked)
+        .and_then(|alert_list| {
+            if !filter.mine {
+                return Ok(alert_list)
+            }
+            let tables = sections::tables_of(&authenticated.staff, &db_locked)?;
+            Ok(
+                alert_list
+                    .into_iter()
+                    .filter(|alert| tables.iter().any(|table| table.name == alert.table))
+                    .collect()
+            )
+        });
+
+    match alert_list {
+        Ok(alerts) => actix_web::HttpResponse::Ok()
+            .json(req::AlertsResponseData {alerts}),
+        Err(err) => {
+            log::error!("{}: {}", logf!("Failed to read value from database!"), err);
+            actix_web::HttpResponse::InternalServerError()
+                .body("Database failed.")
+        }
+    }
+
+}
+
+/// Call waiter and request bill from the guest page, limited to one
+/// alert per table every [`alerts::ALERT_INTERVAL`] seconds.
+#[post("/alerts")]
+pub async fn handler_alerts_insert(
+    data: web::Json<req::AlertsInsertRequestData>,
+    db: web::Data<Arc<Mutex<sled::Db>>>
+) -> impl Responder {
+
+    log::info!("{}", logf!("Entered."));
+
+    let db_locked = match db.lock() {
+        Ok(lock) => lock,
+        Err(_) => {
+            log::error!("{}", logf!("Failed to lock database!"));
+            return actix_web::HttpResponse::InternalServerError()
+                .body("Database is in a deadlock. (DEADLOCK REFERENCE 😳)")
+        }
+    };
+
+    let data = data.into_inner();
+
+    let table = match dbt::VirtualTable::get(
+        dbt::VirtualTable {
+            name: data.table,
+            ..Default::default()
+        }.qualified_identifier(),
+        &db_locked
+    ) {
+        Ok(Some(table)) => table,
+        Ok(None) => return actix_web::HttpResponse::NotFound()
+            .body("Table does not exist!"),
+        Err(err) => {
+            log::error!("{}: {}", logf!("Failed to read value from database!"), err);
+            return actix_web::HttpResponse::InternalServerError()
+                .body("Database failed.")
+        }
+    };
+
+    let authorized = match guest::signing_key(&db_locked) {
+        Ok(key) => data.token.is_some_and(|token| guest::verify(&token, &table, &key)),
+        Err(err) => {
+            log::error!("{}: {}", logf!("Failed to read signing key!"), err);
+            return actix_web::HttpResponse::InternalServerError()
+                .body("Database failed.")
+        }
+    };
+    if !authorized {
+        return actix_web::HttpResponse::Forbidden()
+            .body("This link is not valid for the table, scan the code on the table again.")
+    }
+
+    match alerts::raise(&table.name, data.kind, &db_locked) {
+        Ok(Ok(alert)) => actix_web::HttpResponse::Ok()
+            .json(req::AlertsInsertResponseData {alert}),
+        Ok(Err(seconds)) => actix_web::HttpResponse::TooManyRequests()
+            .body(format!("The staff was just called, try again in {} seconds.", seconds)),
+        Err(err) => {
+            log::error!("{}: {}", logf!("Failed to write value to database!"), err);
+            actix_web::HttpResponse::InternalServerError()
+                .body("Database failed.")
+        }
+    }
+
+}
+
+#[post("/alerts-acknowledge")]
+pub async fn handler_alerts_acknowledge(
+    data: web::Json<req::AlertsAcknowledgeRequestData>,
+    authenticated: web::ReqData<auth::Authenticated>,
+    db: web::Data<Arc<Mutex<sled::Db>>>
+) -> impl Responder {
+
+    log::info!("{}", logf!("Entered."));
+
+    let db_locked = match db.lock() {
+        Ok(lock) => lock,
+        Err(_) => {
+            log::error!("{}", logf!("Failed to lock database!"));
+            return actix_web::HttpResponse::InternalServerError()
+                .body("Database is in a deadlock. (DEADLOCK REFERENCE 😳)")
+        }
+    };
+
+    let alert = match alerts::alert_by_id(data.alert, &db_locked) {
+        Ok(Some(alert)) => alert,
+        Ok(None) => return actix_web::HttpResponse::NotFound()
+            .body("Alert does not exist!"),
+        Err(err) => {
+            log::error!("{}: {}", logf!("Failed to read value from database!"), err);
+            return actix_web::HttpResponse::InternalServerError()
+                .body("Database failed.")
+        }
+    };
+
+    match alerts::acknowledge(alert, &authenticated.staff.name, &db_locked) {
+        Ok(alert) => actix_web::HttpResponse::Ok()
+            .json(req::AlertsAcknowledgeResponseData {alert}),
+        Err(err) => {
+            log::error!("{}: {}", logf!("Failed to write value to database!"), err);
+            actix_web::HttpResponse::InternalServerError()
+                .body("Database failed.")
+        }
+    }
+
+}
+
+#[get("/bills")]
+pub async fn handler_bills(
+    db: web::Data<Arc<Mutex<sled::Db>>>
+) -> impl Responder { 
+
+    log::info!("{}", logf!("Entered."));
+
+    let db_locked = match db.lock() {
+        Ok(lock) => lock,
+        Err(_) => {
+            log::error!("{}", logf!("Failed to lock database!"));
+            return actix_web::HttpResponse::InternalServerError()
+                .body("Database is in a deadlock. (DEADLOCK REFERENCE 😳)")
+        }
+    };
+
+    let template = dbt::Bill {
+        closed: false,
+        ..Default::default()
+    };
+
+    let open_bills = match template.get_status(&db_locked) {
+        Ok(bills) => bills,
+        Err(err) => return billing_error_response(err.into())
+    };
+
+    let mut bills = Vec::new();
+    for bill in open_bills {
+        match billing::summarize(bill, &db_locked) {
+            Ok(summary) => bills.push(summary),
+            Err(err) => return billing_error_response(err.into())
+        }
+    }
+
+    actix_web::HttpResponse::Ok()
+        .json(req::BillsResponseData {bills})
+
+}
+
+#[get("/bills-{id}")]
+pub async fn handler_bills_specific(
+    bill_id: web::Path<dbt::BillID>,
+    db: web::Data<Arc<Mutex<sled::Db>>>
+) -> impl Responder { 
+
+    log::info!("{}", logf!("Entered."));
+
+    let db_locked = match db.lock() {
+        Ok(lock) => lock,
+        Err(_) => {
+            log::error!("{}", logf!("Failed to lock database!"));
+            return actix_web::HttpResponse::InternalServerError()
+                .body("Database is in a deadlock. (DEADLOCK REFERENCE 😳)")
+        }
+    };
+
+    let bill = match db::bill_by_id(bill_id.into_inner(), &db_locked) {
+        Ok(Some(bill)) => bill,
+        Ok(None) => return billing_error_response(
+            billing::BillingError::NotFound("Bill does not exist!".to_string())
+        ),
+        Err(err) => return billing_error_response(err.into())
+    };
+
+    match billing::summarize(bill, &db_locked) {
+        Ok(bill) => actix_web::HttpResponse::Ok()
+            .json(req::BillsSpecificResponseData {bill}),
+        Err(err) => billing_error_response(err.into())
+    }
+
+}
+
+#[post("/bills")]
+pub async fn handler_bills_open(
+    data: web::Json<req::BillsOpenRequestData>,
+    db: web::Data<Arc<Mutex<sled::Db>>>
+) -> impl Responder { 
+
+    log::info!("{}", logf!("Entered."));
+
+    let db_locked = match db.lock() {
+        Ok(lock) => lock,
+        Err(_) => {
+            log::error!("{}", logf!("Failed to lock database!"));
+            return actix_web::HttpResponse::InternalServerError()
+                .body("Database is in a deadlock. (DEADLOCK REFERENCE 😳)")
+        }
+    };
+
+    let bill = match billing::open_bill(&data.into_inner().table, &db_locked) {
+        Ok(bill) => bill,
+        Err(err) => return billing_error_response(err)
+    };
+
+    match billing::summarize(bill, &db_locked) {
+        Ok(bill) => actix_web::HttpResponse::Ok()
+            .json(req::BillsOpenResponseData {bill}),
+        Err(err) => billing_error_response(err.into())
+    }
+
+}
+
+#[post("/bills-pay")]
+pub async fn handler_bills_pay(
+    data: web::Json<req::BillsPayRequestData>,
+    db: web::Data<Arc<Mutex<sled::Db>>>
+) -> impl Responder { 
+
+    log::info!("{}", logf!("Entered."));
+
+    let db_locked = match db.lock() {
+        Ok(lock) => lock,
+        Err(_) => {
+            log::error!("{}", logf!("Failed to lock database!"));
+            return actix_web::HttpResponse::InternalServerError()
+                .body("Database is in a deadlock. (DEADLOCK REFERENCE 😳)")
+        }
+    };
+
+    match billing::pay(data.into_inner(), &db_locked) {
+        Ok((payment, bill)) => actix_web::HttpResponse::Ok()
+            .json(req::BillsPayResponseData {payment, bill}),
+        Err(err) => billing_error_response(err)
+    }
+
+}
+
+#[get("/bills-{id}/split-{ways}")]
+pub async fn handler_bills_split(
+    path: web::Path<(dbt::BillID, u32)>,
+    db: web::Data<Arc<Mutex<sled::Db>>>
+) -> impl Responder { 
+
+    log::info!("{}", logf!("Entered."));
+
+    let db_locked = match db.lock() {
+        Ok(lock) => lock,
+        Err(_) => {
+            log::error!("{}", logf!("Failed to lock database!"));
+            return actix_web::HttpResponse::InternalServerError()
+                .body("Database is in a deadlock. (DEADLOCK REFERENCE 😳)")
+        }
+    };
+
+    let (bill_id, ways) = path.into_inner();
+
+    if ways == 0 {
+        return billing_error_response(
+            billing::BillingError::Invalid("Can't split a bill zero ways.".to_string())
+        )
+    }
+
+    let bill = match db::bill_by_id(bill_id, &db_locked) {
+        Ok(Some(bill)) => bill,
+        Ok(None) => return billing_error_response(
+            billing::BillingError::NotFound("Bill does not exist!".to_string())
+        ),
+        Err(err) => return billing_error_response(err.into())
+    };
+
+    match billing::summarize(bill, &db_locked) {
+        Ok(summary) => actix_web::HttpResponse::Ok()
+            .json(req::BillsSplitResponseData {
+                shares: billing::split_evenly(summary.remaining, ways)
+            }),
+        Err(err) => billing_error_response(err.into())
+    }
+
+}
+
+#[post("/bills-close")]
+pub async fn handler_bills_close(
+    data: web::Json<req::BillsCloseRequestData>,
+    db: web::Data<Arc<Mutex<sled::Db>>>
+) -> impl Responder { 
+
+    log::info!("{}", logf!("Entered."));
+
+    let db_locked = match db.lock() {
+        Ok(lock) => lock,
+        Err(_) => {
+            log::error!("{}", logf!("Failed to lock database!"));
+            return actix_web::HttpResponse::InternalServerError()
+                .body("Database is in a deadlock. (DEADLOCK REFERENCE 😳)")
+        }
+    };
+
+    // The receipt is issued separately, for the whole bill or for
+    // each of its orders.
+    match billing::close(data.into_inner().bill, &db_locked) {
+        Ok(bill) => actix_web::HttpResponse::Ok()
+            .json(req::BillsCloseResponseData {bill}),
+        Err(err) => billing_error_response(err)
+    }
+
+}
+
+#[post("/receipts")]
+pub async fn handler_receipts_issue(
+    data: web::Json<req::ReceiptsIssueRequestData>,
+    db: web::Data<Arc<Mutex<sled::Db>>>
+) -> impl Responder { 
+
+    log::info!("{}", logf!("Entered."));
+
+    let db_locked = match db.lock() {
+        Ok(lock) => lock,
+        Err(_) => {
+            log::error!("{}", logf!("Failed to lock database!"));
+            return actix_web::HttpResponse::InternalServerError()
+                .body("Database is in a deadlock. (DEADLOCK REFERENCE 😳)")
+        }
+    };
+
+    let issued = match data.into_inner() {
+        req::ReceiptsIssueRequestData { bill: Some(bill), order: None } => {
+            match db::bill_by_id(bill, &db_locked) {
+                Ok(Some(bill)) => match billing::summarize(bill, &db_locked) {
+                    Ok(summary) => receipt::issue_for_bill(&summary, &db_locked),
+                    Err(err) => Err(err.into())
+                },
+                Ok(None) => Err(billing::BillingError::NotFound("Bill does not exist!".to_string())),
+                Err(err) => Err(err.into())
+            }
+        }
+        req::ReceiptsIssueRequestData { bill: None, order: Some(order) } => {
+            receipt::issue_for_order(&order, &db_locked)
+        }
+        _ => Err(billing::BillingError::Invalid(
+            "Either a bill or an order is needed, not both.".to_string()
+        ))
+    };
+
+    match issued {
+        Ok(receipt) => actix_web::HttpResponse::Ok()
+            .json(req::ReceiptsIssueResponseData {receipt}),
+        Err(err) => billing_error_response(err)
+    }
+
+}
+
+fn receipt_or_response(
+    year: i32,
+    number: u32,
+    db: &sled::Db
+) -> Result<dbt::Receipt, actix_web::HttpResponse> {
+
+    match receipt::receipt_by_id(&dbt::ReceiptID {year, number}, db) {
+        Ok(Some(receipt)) => Ok(receipt),
+        Ok(None) => Err(billing_error_response(
+            billing::BillingError::NotFound("Receipt does not exist!".to_string())
+        )),
+        Err(err) => Err(billing_error_response(err.into()))
+    }
+
+}
+
+#[get("/receipts-{year:\\d+}-{number:\\d+}")]
+pub async fn handler_receipts_specific(
+    path: web::Path<(i32, u32)>,
+    db: web::Data<Arc<Mutex<sled::Db>>>
+) -> impl Responder { 
+
+    log::info!("{}", logf!("Entered."));
+
+    let db_locked = match db.lock() {
+        Ok(lock) => lock,
+        Err(_) => {
+            log::error!("{}", logf!("Failed to lock database!"));
+            return actix_web::HttpResponse::InternalServerError()
+                .body("Database is in a deadlock. (DEADLOCK REFERENCE 😳)")
+        }
+    };
+
+    let (year, number) = path.into_inner();
+
+    match receipt_or_response(year, number, &db_locked) {
+        Ok(receipt) => actix_web::HttpResponse::Ok()
+            .json(req::ReceiptsSpecificResponseData {receipt}),
+        Err(response) => response
+    }
+
+}
+
+#[get("/receipts-{year:\\d+}-{number:\\d+}/{format}")]
+pub async fn handler_receipts_render(
+    path: web::Path<(i32, u32, String)>,
+    db: web::Data<Arc<Mutex<sled::Db>>>
+) -> impl Responder { 
+
+    log::info!("{}", logf!("Entered."));
+
+    let db_locked = match db.lock() {
+        Ok(lock) => lock,
+        Err(_) => {
+            log::error!("{}", logf!("Failed to lock database!"));
+            return actix_web::HttpResponse::InternalServerError()
+                .body("Database is in a deadlock. (DEADLOCK REFERENCE 😳)")
+        }
+    };
+
+    let (year, number, format) = path.into_inner();
+
+    let receipt = match receipt_or_response(year, number, &db_locked) {
+        Ok(receipt) => receipt,
+        Err(response) => return response
+    };
+
+    match format.as_str() {
+        "text" => actix_web::HttpResponse::Ok()
+            .content_type("text/plain; charset=utf-8")
+            .body(receipt::render_text(&receipt)),
+        "html" => actix_web::HttpResponse::Ok()
+            .content_type("text/html; charset=utf-8")
+            .body(receipt::render_html(&receipt)),
+        "pdf" => actix_web::HttpResponse::Ok()
+            .content_type("application/pdf")
+            .insert_header((
+                "Content-Disposition",
+                format!("inline; filename=\"receipt-{}.pdf\"", receipt.id)
+            ))
+            .body(receipt::render_pdf(&receipt)),
+        _ => actix_web::HttpResponse::BadRequest()
+            .body("Format has to be one of `text`, `html` or `pdf`.")
+    }
+
+}
+
+#[get("/venue")]
+pub async fn handler_venue(
+    db: web::Data<Arc<Mutex<sled::Db>>>
+) -> impl Responder { 
+
+    log::info!("{}", logf!("Entered."));
+
+    let db_locked = match db.lock() {
+        Ok(lock) => lock,
+        Err(_) => {
+            log::error!("{}", logf!("Failed to lock database!"));
+            return actix_web::HttpResponse::InternalServerError()
+                .body("Database is in a deadlock. (DEADLOCK REFERENCE 😳)")
+        }
+    };
+
+    match dbt::Venue::get(dbt::Venue::default().qualified_identifier(), &db_locked) {
+        Ok(venue) => actix_web::HttpResponse::Ok()
+            .json(req::VenueResponseData {venue: venue.unwrap_or_default()}),
+        Err(err) => {
+            log::error!("{}: {}", logf!("Failed to read value from database!"), err);
+            actix_web::HttpResponse::InternalServerError()
+                .body("Database failed.")
+        }
+    }
+
+}
+
+#[post("/venue")]
+pub async fn handler_venue_insert(
+    request_data: web::Json<req::VenueInsertRequestData>,
+    db: web::Data<Arc<Mutex<sled::Db>>>
+) -> impl Responder { 
+
+    log::info!("{}", logf!("Entered."));
+
+    let db_locked = match db.lock() {
+        Ok(lock) => lock,
+        Err(_) => {
+            log::error!("{}", logf!("Failed to lock database!"));
+            return actix_web::HttpResponse::InternalServerError()
+                .body("Database is in a deadlock. (DEADLOCK REFERENCE 😳)")
+        }
+    };
+
+    match request_data.into_inner().venue.insert(&db_locked) {
+        Ok(()) => actix_web::HttpResponse::Ok()
+            .body("Successfully updated the venue."),
+        Err(err) => {
+            log::error!("{}: {}", logf!("Failed to write value to database!"), err);
+            actix_web::HttpResponse::InternalServerError()
+                .body("Database failed.")
+        }
+    }
+
+}
+
+#[get("/fiscal")]
+pub async fn handler_fiscal(
+    db: web::Data<Arc<Mutex<sled::Db>>>
+) -> impl Responder { 
+
+    log::info!("{}", logf!("Entered."));
+
+    let db_locked = match db.lock() {
+        Ok(lock) => lock,
+        Err(_) => {
+            log::error!("{}", logf!("Failed to lock database!"));
+            return actix_web::HttpResponse::InternalServerError()
+                .body("Database is in a deadlock. (DEADLOCK REFERENCE 😳)")
+        }
+    };
+
+    match fiscal::settings(&db_locked) {
+        Ok(settings) => actix_web::HttpResponse::Ok()
+            .json(req::FiscalResponseData {settings}),
+        Err(err) => {
+            log::error!("{}: {}", logf!("Failed to read value from database!"), err);
+            actix_web::HttpResponse::InternalServerError()
+                .body("Database failed.")
+        }
+    }
+
+}
+
+#[post("/fiscal")]
+pub async fn handler_fiscal_insert(
+    request_data: web::Json<req::FiscalInsertRequestData>,
+    db: web::Data<Arc<Mutex<sled::Db>>>
+) -> impl Responder { 
+
+    log::info!("{}", logf!("Entered."));
+
+    let db_locked = match db.lock() {
+        Ok(lock) => lock,
+        Err(_) => {
+            log::error!("{}", logf!("Failed to lock database!"));
+            return actix_web::HttpResponse::InternalServerError()
+                .body("Database is in a deadlock. (DEADLOCK REFERENCE 😳)")
+        }
+    };
+
+    let settings = request_data.into_inner().settings;
+
+    if settings.enabled {
+        if let Err(err) = fiscal::RsaSigner::from_pem_file(&settings.key_path) {
+            return actix_web::HttpResponse::BadRequest()
+                .body(err)
+        }
+        if settings.premises.is_empty() || settings.device.is_empty() {
+            return actix_web::HttpResponse::BadRequest()
+                .body("Premises and device codes are required.")
+        }
+    }
+
+    match settings.insert(&db_locked) {
+        Ok(()) => actix_web::HttpResponse::Ok()
+            .body("Successfully updated the fiscal settings."),
+        Err(err) => {
+            log::error!("{}: {}", logf!("Failed to write value to database!"), err);
+            actix_web::HttpResponse::InternalServerError()
+                .body("Database failed.")
+        }
+    }
+
+}
+
+#[get("/fiscal-pending")]
+pub async fn handler_fiscal_pending(
+    db: web::Data<Arc<Mutex<sled::Db>>>
+) -> impl Responder { 
+
+    log::info!("{}", logf!("Entered."));
+
+    let db_locked = match db.lock() {
+        Ok(lock) => lock,
+        Err(_) => {
+            log::error!("{}", logf!("Failed to lock database!"));
+            return actix_web::HttpResponse::InternalServerError()
+                .body("Database is in a deadlock. (DEADLOCK REFERENCE 😳)")
+        }
+    };
+
+    match fiscal::pending(&db_locked) {
+        Ok(receipts) => actix_web::HttpResponse::Ok()
+            .json(req::FiscalPendingResponseData {receipts}),
+        Err(err) => {
+            log::error!("{}: {}", logf!("Failed to read value from database!"), err);
+            actix_web::HttpResponse::InternalServerError()
+                .body("Database failed.")
+        }
+    }
+
+}
+
+#[post("/fiscal-retry")]
+pub async fn handler_fiscal_retry(
+    db: web::Data<Arc<Mutex<sled::Db>>>
+) -> impl Responder { 
+
+    log::info!("{}", logf!("Entered."));
+
+    let db_locked = match db.lock() {
+        Ok(lock) => lock,
+        Err(_) => {
+            log::error!("{}", logf!("Failed to lock database!"));
+            return actix_web::HttpResponse::InternalServerError()
+                .body("Database is in a deadlock. (DEADLOCK REFERENCE 😳)")
+        }
+    };
+
+    match fiscal::retry_pending(&db_locked) {
+        Ok((sent, pending)) => actix_web::HttpResponse::Ok()
+            .json(req::FiscalRetryResponseData {sent, pending}),
+        Err(err) => {
+            log::error!("{}: {}", logf!("Failed to write value to database!"), err);
+            actix_web::HttpResponse::InternalServerError()
+                .body("Database failed.")
+        }
+    }
+
+}
+
+#[get("/reports-{date}")]
+pub async fn handler_reports_daily(
+    date: web::Path<String>,
+    db: web::Data<Arc<Mutex<sled::Db>>>
+) -> impl Responder { 
+
+    log::info!("{}", logf!("Entered."));
+
+    let db_locked = match db.lock() {
+        Ok(lock) => lock,
+        Err(_) => {
+            log::error!("{}", logf!("Failed to lock database!"));
+            return actix_web::HttpResponse::InternalServerError()
+                .body("Database is in a deadlock. (DEADLOCK REFERENCE 😳)")
+        }
+    };
+
+    let date = match chrono::NaiveDate::parse_from_str(&date.into_inner(), "%Y-%m-%d") {
+        Ok(date) => date,
+        Err(_) => {
+            return actix_web::HttpResponse::BadRequest()
+                .body("Date has to be in the `YYYY-MM-DD` format.")
+        }
+    };
+
+    match reports::daily(date, &db_locked) {
+        Ok(report) => actix_web::HttpResponse::Ok()
+            .json(report),
+        Err(err) => {
+            log::error!("{}: {}", logf!("Failed to read value from database!"), err);
+            actix_web::HttpResponse::InternalServerError()
+                .body("Database failed.")
+        }
+    }
+
+}
+
+#[post("/login")]
+pub async fn handler_login(
+    request_data: web::Json<req::LoginRequestData>,
+    db: web::Data<Arc<Mutex<sled::Db>>>
+) -> impl Responder {
+
+    log::info!("{}", logf!("Entered."));
+
+    let db_locked = match db.lock() {
+        Ok(lock) => lock,
+        Err(_) => {
+            log::error!("{}", logf!("Failed to lock database!"));
+            return actix_web::HttpResponse::InternalServerError()
+                .body("Database is in a deadlock. (DEADLOCK REFERENCE 😳)")
+        }
+    };
+
+    let request_data = request_data.into_inner();
+
+    match auth::login(&request_data.name, &request_data.secret, &db_locked) {
+        Ok((token, staff)) => actix_web::HttpResponse::Ok()
+            .json(req::LoginResponseData {
+                token: token.token,
+                expires_at: token.expires_at,
+                staff: auth::account(&staff)
+            }),
+        Err(auth::LoginError::Invalid) => {
+            log::info!("{}: `{}`", logf!("Failed login"), request_data.name);
+            actix_web::HttpResponse::Unauthorized()
+                .body("Wrong name or PIN.")
+        }
+        Err(auth::LoginError::Locked(seconds)) => actix_web::HttpResponse::TooManyRequests()
+            .body(format!("Too many failed logins, try again in {} seconds.", seconds)),
+        Err(auth::LoginError::Database(err)) => {
+            log::error!("{}: {}", logf!("Database failed!"), err);
+            actix_web::HttpResponse::InternalServerError()
+                .body("Database failed.")
+        }
+    }
+
+}
+
+#[post("/logout")]
+pub async fn handler_logout(
+    authenticated: web::ReqData<auth::Authenticated>,
+    db: web::Data<Arc<Mutex<sled::Db>>>
+) -> impl Responder {
+
+    log::info!("{}", logf!("Entered."));
+
+    let db_locked = match db.lock() {
+        Ok(lock) => lock,
+        Err(_) => {
+            log::error!("{}", logf!("Failed to lock database!"));
+            return actix_web::HttpResponse::InternalServerError()
+                .body("Database is in a deadlock. (DEADLOCK REFERENCE 😳)")
+        }
+    };
+
+    let template = dbt::StaffToken {
+        token: authenticated.into_inner().token,
+        ..Default::default()
+    };
+
+    match template.remove(&db_locked) {
+        Ok(()) => actix_web::HttpResponse::Ok()
+            .body("Logged out."),
+        Err(err) => {
+            log::error!("{}: {}", logf!("Failed to remove value from database!"), err);
+            actix_web::HttpResponse::InternalServerError()
+                .body("Database failed.")
+        }
+    }
+
+}
+
+#[get("/me")]
+pub async fn handler_me(
+    authenticated: web::ReqData<auth::Authenticated>
+) -> impl Responder {
+
+    log::info!("{}", logf!("Entered."));
+
+    actix_web::HttpResponse::Ok()
+        .json(auth::account(&authenticated.staff))
+
+}
+
+#[get("/staff")]
+pub async fn handler_staff(
+    db: web::Data<Arc<Mutex<sled::Db>>>
+) -> impl Responder {
+
+    log::info!("{}", logf!("Entered."));
+
+    let db_locked = match db.lock() {
+        Ok(lock) => lock,
+        Err(_) => {
+            log::error!("{}", logf!("Failed to lock database!"));
+            return actix_web::HttpResponse::InternalServerError()
+                .body("Database is in a deadlock. (DEADLOCK REFERENCE 😳)")
+        }
+    };
+
+    match dbt::Staff::get_all(&db_locked) {
+        Ok(staff) => actix_web::HttpResponse::Ok()
+            .json(req::StaffResponseData {
+                staff: staff.iter().map(auth::account).collect()
+            }),
+        Err(err) => {
+            log::error!("{}: {}", logf!("Failed to read value from database!"), err);
+            actix_web::HttpResponse::InternalServerError()
+                .body("Database failed.")
+        }
+    }
+
+}
+
+#[post("/staff")]
+pub async fn handler_staff_insert(
+    request_data: web::Json<req::StaffInsertRequestData>,
+    authenticated: web::ReqData<auth::Authenticated>,
+    db: web::Data<Arc<Mutex<sled::Db>>>
+) -> impl Responder {
+
+    log::info!("{}", logf!("Entered."));
+
+    let db_locked = match db.lock() {
+        Ok(lock) => lock,
+        Err(_) => {
+            log::error!("{}", logf!("Failed to lock database!"));
+            return actix_web::HttpResponse::InternalServerError()
+                .body("Database is in a deadlock. (DEADLOCK REFERENCE 😳)")
+        }
+    };
+
+    let request_data = request_data.into_inner();
+
+    if request_data.name.trim().is_empty() || request_data.name.contains('/') {
+        return actix_web::HttpResponse::BadRequest()
+            .body("Name can't be empty or contain `/`.")
+    }
+
+    let existing = match dbt::Staff::get(
+        dbt::Staff {
+            name: request_data.name.clone(),
+            ..Default::default()
+        }.qualified_identifier(),
+        &db_locked
+    ) {
+        Ok(existing) => existing,
+        Err(err) => {
+            log::error!("{}: {}", logf!("Failed to read value from database!"), err);
+            return actix_web::HttpResponse::InternalServerError()
+                .body("Database failed.")
+        }
+    };
+
+    // Manager accounts, and making one, are left to managers.
+    if !existing
+        .iter()
+        .map(|existing| existing.role)
+        .chain([request_data.role])
+        .all(|role| auth::may_manage(&authenticated.staff, role))
+    {
+        return actix_web::HttpResponse::Forbidden()
+            .body("Only managers can create or change manager accounts.")
+    }
+
+    let secret_hash = match (&request_data.secret, &existing) {
+        (Some(secret), _) => {
+            if let Err(reason) = auth::check_secret(secret) {
+                return actix_web::HttpResponse::BadRequest()
+                    .body(reason)
+            }
+            match auth::hash_secret(secret) {
+                Ok(hash) => hash,
+                Err(err) => {
+                    log::error!("{}: {}", logf!("Failed to hash secret!"), err);
+                    return actix_web::HttpResponse::InternalServerError()
+                        .body("Failed to hash the PIN or password.")
+                }
+            }
+        }
+        (None, Some(existing)) => existing.secret_hash.clone(),
+        (None, None) => {
+            return actix_web::HttpResponse::BadRequest()
+                .body("A new account needs a PIN or password.")
+        }
+    };
+
+    let staff = dbt::Staff {
+        name: request_data.name,
+        role: request_data.role,
+        permissions: request_data.permissions,
+        secret_hash,
+        disabled: request_data.disabled,
+        failed_logins: 0,
+        locked_until: None,
+        tables: existing.as_ref().map_or_else(Vec::new, |existing| existing.tables.clone()),
+        zones: existing.as_ref().map_or_else(Vec::new, |existing| existing.zones.clone())
+    };
+
+    if !auth::may_grant(&authenticated.staff, existing.as_ref(), &staff) {
+        return actix_web::HttpResponse::Forbidden()
+            .body("Only managers can grant permissions they don't have themselves.")
+    }
+
+    let was_admin = existing.as_ref().is_some_and(|existing| {
+        !existing.disabled && auth::authority(existing.role) == req::Authority::Admin
+    });
+    let is_admin = !staff.disabled && auth::authority(staff.role) == req::Authority::Admin;
+
+    if was_admin && !is_admin {
+        match auth::other_manager_exists(&staff.name, &db_locked) {
+            Ok(true) => (),
+            Ok(false) => {
+                return actix_web::HttpResponse::BadRequest()
+                    .body("At least one active manager has to remain.")
+            }
+            Err(err) => {
+                log::error!("{}: {}", logf!("Failed to read value from database!"), err);
+                return actix_web::HttpResponse::InternalServerError()
+                    .body("Database failed.")
+            }
+        }
+    }
+
+    // A changed secret or a disabled account logs out everywhere.
+    let revoke = existing.is_some() && (request_data.secret.is_some() || staff.disabled);
+
+    match staff.insert(&db_locked).and_then(|()| {
+        if revoke {
+            auth::revoke_tokens(&staff.name, &db_locked)
+        } else {
+            Ok(())
+        }
+    }) {
+        Ok(()) => actix_web::HttpResponse::Ok()
+            .json(auth::account(&staff)),
+        Err(err) => {
+            log::error!("{}: {}", logf!("Failed to write value to database!"), err);
+            actix_web::HttpResponse::InternalServerError()
+                .body("Database failed.")
+        }
+    }
+
+}
+
+#[post("/staff-assign")]
+pub async fn handler_staff_assign(
+    request_data: web::Json<req::StaffAssignRequestData>,
+    db: web::Data<Arc<Mutex<sled::Db>>>
+) -> impl Responder {
+
+    log::info!("{}", logf!("Entered."));
+
+    let db_locked = match db.lock() {
+        Ok(lock) => lock,
+        Err(_) => {
+            log::error!("{}", logf!("Failed to lock database!"));
+            return actix_web::HttpResponse::InternalServerError()
+                .body("Database is in a deadlock. (DEADLOCK REFERENCE 😳)")
+        }
+    };
+
+    let request_data = request_data.into_inner();
+
+    match sections::check(&request_data.tables, &request_data.zones, &db_locked) {
+        Ok(Ok(())) => (),
+        Ok(Err(reason)) => {
+            return actix_web::HttpResponse::BadRequest()
+                .body(reason)
+        }
+        Err(err) => {
+            log::error!("{}: {}", logf!("Failed to read value from database!"), err);
+            return actix_web::HttpResponse::InternalServerError()
+                .body("Database failed.")
+        }
+    }
+
+    let mut staff = match dbt::Staff::get(
+        dbt::Staff {
+            name: request_data.staff,
+            ..Default::default()
+        }.qualified_identifier(),
+        &db_locked
+    ) {
+        Ok(Some(staff)) => staff,
+        Ok(None) => {
+            return actix_web::HttpResponse::NotFound()
+                .body("Staff member does not exist!")
+        }
+        Err(err) => {
+            log::error!("{}: {}", logf!("Failed to read value from database!"), err);
+            return actix_web::HttpResponse::InternalServerError()
+                .body("Database failed.")
+        }
+    };
+
+    staff.tables = request_data.tables;
+    staff.zones = request_data.zones;
+
+    match staff.insert(&db_locked) {
+        Ok(()) => actix_web::HttpResponse::Ok()
+            .json(auth::account(&staff)),
+        Err(err) => {
+            log::error!("{}: {}", logf!("Failed to write value to database!"), err);
+            actix_web::HttpResponse::InternalServerError()
+                .body("Database failed.")
+        }
+    }
+
+}
+
+#[get("/my-tables")]
+pub async fn handler_my_tables(
+    authenticated: web::ReqData<auth::Authenticated>,
+    db: web::Data<Arc<Mutex<sled::Db>>>
+) -> impl Responder {
+
+    log::info!("{}", logf!("Entered."));
+
+    let db_locked = match db.lock() {
+        Ok(lock) => lock,
+        Err(_) => {
+            log::error!("{}", logf!("Failed to lock database!"));
+            return actix_web::HttpResponse::InternalServerError()
+                .body("Database is in a deadlock. (DEADLOCK REFERENCE 😳)")
+        }
+    };
+
+    match sections::tables_of(&authenticated.staff, &db_locked) {
+        Ok(tables) => actix_web::HttpResponse::Ok()
+            .json(req::TablesResponseData {tables}),
+        Err(err) => {
+            log::error!("{}: {}", logf!("Failed to read value from database!"), err);
+            actix_web::HttpResponse::InternalServerError()
+                .body("Database failed.")
+        }
+    }
+
+}
+
+#[get("/my-orders")]
+pub async fn handler_my_orders(
+    authenticated: web::ReqData<auth::Authenticated>,
+    db: web::Data<Arc<Mutex<sled::Db>>>
+) -> impl Responder {
+
+    log::info!("{}", logf!("Entered."));
+
+    let db_locked = match db.lock() {
+        Ok(lock) => lock,
+        Err(_) => {
+            log::error!("{}", logf!("Failed to lock database!"));
             return actix_web::HttpResponse::InternalServerError()
+                .body("Database is in a deadlock. (DEADLOCK REFERENCE 😳)")
+        }
+    };
+
+    match sections::open_orders_of(&authenticated.staff, &db_locked) {
+        Ok(orders) => actix_web::HttpResponse::Ok()
+            .json(req::MyOrdersResponseData {
+                orders: orders.into_iter().map(req::TotaledOrder::from).collect()
+            }),
+        Err(err) => {
+            log::error!("{}: {}", logf!("Failed to read value from database!"), err);
+            actix_web::HttpResponse::InternalServerError()
                 .body("Database failed.")
         }
     }
 
 }
 
+#[delete("/staff/{id}")]
+pub async fn handler_staff_delete(
+    staff_id: web::Path<dbt::StaffID>,
+    authenticated: web::ReqData<auth::Authenticated>,
+    db: web::Data<Arc<Mutex<sled::Db>>>
+) -> impl Responder {
+
+    log::info!("{}", logf!("Entered."));
+
+    let db_locked = match db.lock() {
+        Ok(lock) => lock,
+        Err(_) => {
+            log::error!("{}", logf!("Failed to lock database!"));
+            return actix_web::HttpResponse::InternalServerError()
+                .body("Database is in a deadlock. (DEADLOCK REFERENCE 😳)")
+        }
+    };
+
+    let staff_id = staff_id.into_inner();
+
+    let template = dbt::Staff {
+        name: staff_id.clone(),
+        ..Default::default()
+    };
+
+    match dbt::Staff::get(template.qualified_identifier(), &db_locked) {
+        Ok(Some(existing)) if !auth::may_manage(&authenticated.staff, existing.role) => {
+            return actix_web::HttpResponse::Forbidden()
+                .body("Only managers can remove manager accounts.")
+        }
+        Ok(_) => (),
+        Err(err) => {
+            log::error!("{}: {}", logf!("Failed to read value from database!"), err);
+            return actix_web::HttpResponse::InternalServerError()
+                .body("Database failed.")
+        }
+    }
+
+    match auth::other_manager_exists(&staff_id, &db_locked) {
+        Ok(true) => (),
+        Ok(false) => {
+            return actix_web::HttpResponse::BadRequest()
+                .body("At least one active manager has to remain.")
+        }
+        Err(err) => {
+            log::error!("{}: {}", logf!("Failed to read value from database!"), err);
+            return actix_web::HttpResponse::InternalServerError()
+                .body("Database failed.")
+        }
+    }
+
+    match template.remove(&db_locked).and_then(|()| auth::revoke_tokens(&staff_id, &db_locked)) {
+        Ok(()) => actix_web::HttpResponse::Ok()
+            .body("Successfully removed the staff member."),
+        Err(err) => {
+            log::error!("{}: {}", logf!("Failed to remove value from database!"), err);
+            actix_web::HttpResponse::InternalServerError()
+                .body("Database failed.")
+        }
+    }
+
+}
 
 #[get("/offers-tables")]
 pub async fn handler_offers_tables(
@@ -602,9 +3653,56 @@ pub async fn handler_offers_tables(
 
 
 
+/// Reads a page out of `index/`.
+fn static_page(file: &str) -> actix_web::HttpResponse {
+
+    let path = [
+        env!("CARGO_MANIFEST_DIR"),
+        "index",
+        file
+    ].join("/");
+
+    match std::fs::read_to_string(path) {
+        Ok(page) => actix_web::HttpResponse::Ok()
+            .content_type("text/html")
+            .body(page),
+        Err(err) => {
+            log::error!("{}: {}", logf!("Failed to read the page!"), err);
+            actix_web::HttpResponse::InternalServerError()
+                .body("Page is missing.")
+        }
+    }
+
+}
+
+/// Kitchen display for the station screens, it logs in and talks to
+/// the database server on its own. Has to be registered before
+/// [`handler_server`], which would take `kds` for a table name.
+#[get("/kds")]
+pub async fn handler_kds() -> impl Responder {
+
+    log::info!("{}", logf!("Entered."));
+
+    static_page("kds.html")
+
+}
+
+/// Dashboard for managing the menu, tables, staff and settings,
+/// built on the same endpoints as the client. Has to be registered
+/// before [`handler_server`] like [`handler_kds`].
+#[get("/admin")]
+pub async fn handler_admin() -> impl Responder {
+
+    log::info!("{}", logf!("Entered."));
+
+    static_page("admin.html")
+
+}
+
 #[get("/{id}")]
 pub async fn handler_server(
     table_id: web::Path<dbt::VirtualTableID>,
+    query: web::Query<req::GuestPageRequestData>,
     db: web::Data<Arc<Mutex<sled::Db>>>
 ) -> impl Responder { 
 
@@ -633,17 +3731,23 @@ pub async fn handler_server(
             .body("Database failed.")
     };
 
-    let mut set = HashSet::new();
-    for table in tables {
-        set.insert(table.name.clone());
-    }
+    let key = match guest::signing_key(&db_locked) {
+        Ok(key) => key,
+        Err(_) => return actix_web::HttpResponse::InternalServerError()
+            .body("Database failed.")
+    };
 
-    if set.contains(&table_id.into_inner()) {
-        return actix_web::HttpResponse::Ok()
-            .content_type("text/html")
-            .body(string)  
-    } else {
-        return actix_web::HttpResponse::InternalServerError()
+    let table_id = table_id.into_inner();
+
+    match tables.iter().find(|table| table.name == table_id) {
+        Some(table) if query.token.as_ref().is_some_and(|token| guest::verify(token, table, &key)) => {
+            actix_web::HttpResponse::Ok()
+                .content_type("text/html")
+                .body(string)
+        }
+        Some(_) => actix_web::HttpResponse::Forbidden()
+            .body("This link is no longer valid, scan the code on the table again."),
+        None => actix_web::HttpResponse::InternalServerError()
             .body("Table does not exist!")
     }
 
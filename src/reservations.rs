@@ -0,0 +1,168 @@
+use chrono::{NaiveDate, NaiveDateTime, NaiveTime, TimeDelta};
+
+use crate::db::DatabaseElement;
+use crate::shared::dbt as dbt;
+
+pub fn parse_date(date: &str) -> Option<NaiveDate> {
+    NaiveDate::parse_from_str(date, "%Y-%m-%d").ok()
+}
+
+/// Local start and end of the reservation, `None` if its date or
+/// time is invalid.
+fn slot(reservation: &dbt::Reservation) -> Option<(NaiveDateTime, NaiveDateTime)> {
+
+    let time = NaiveTime::from_hms_opt(
+        reservation.start.hour as u32,
+        reservation.start.minute as u32,
+        0
+    )?;
+    let start = parse_date(&reservation.date)?.and_time(time);
+
+    Some((start, start + TimeDelta::minutes(reservation.duration as i64)))
+
+}
+
+fn overlap(a: &dbt::Reservation, b: &dbt::Reservation) -> bool {
+    match (slot(a), slot(b)) {
+        (Some((a_start, a_end)), Some((b_start, b_end))) => a_start < b_end && b_start < a_end,
+        _ => false
+    }
+}
+
+/// Bookings of a day, sorted by time.
+pub fn reservations_of_day(date: NaiveDate, db: &sled::Db) -> Result<Vec<dbt::Reservation>, String> {
+
+    let date = date.format("%Y-%m-%d").to_string();
+
+    let mut reservations = dbt::Reservation {
+        date: date.clone(),
+        ..Default::default()
+    }
+    .get_templated(db)?
+    .into_iter()
+    .filter(|reservation| reservation.date == date)
+    .collect::<Vec<dbt::Reservation>>();
+
+    reservations.sort_by_key(|reservation| reservation.start);
+
+    Ok(reservations)
+
+}
+
+pub fn reservation_by_id(id: dbt::ReservationID, db: &sled::Db) -> Result<Option<dbt::Reservation>, String> {
+
+    Ok(
+        dbt::Reservation::get_all(db)?
+            .into_iter()
+            .find(|reservation| reservation.id == id)
+    )
+
+}
+
+/// Everything that keeps the reservation from being booked, empty if
+/// it can be.
+///
+/// A reservation for a specific table needs the table to seat the
+/// party and to be free for the whole slot. One for a zone needs a
+/// fitting table of the zone that isn't taken by other reservations
+/// at the time, counting bookings for the zone as taking one table
+/// each.
+pub fn conflicts(reservation: &dbt::Reservation, db: &sled::Db) -> Result<Vec<String>, String> {
+
+    let mut conflicts = Vec::new();
+
+    if reservation.guest.trim().is_empty() {
+        conflicts.push("Reservation needs the guest's name.".to_string());
+    }
+    if reservation.party_size == 0 {
+        conflicts.push("Party size has to be at least one.".to_string());
+    }
+    if reservation.duration == 0 {
+        conflicts.push("Duration has to be at least a minute.".to_string());
+    }
+
+    let date = match (parse_date(&reservation.date), slot(reservation)) {
+        (Some(date), Some(_)) => date,
+        _ => {
+            conflicts.push("Date has to be `YYYY-MM-DD` and time a valid time of day.".to_string());
+            return Ok(conflicts)
+        }
+    };
+
+    // A late booking the day before can still run into this one.
+    let mut others = Vec::new();
+    for day in [date.pred_opt(), Some(date), date.succ_opt()].into_iter().flatten() {
+        others.extend(
+            reservations_of_day(day, db)?
+                .into_iter()
+                .filter(|other| {
+                    other.id != reservation.id
+                    && other.status.is_active()
+                    && overlap(reservation, other)
+                })
+        );
+    }
+
+    let tables = dbt::VirtualTable::get_all(db)?;
+    let fits = |table: &dbt::VirtualTable| {
+        table.capacity == 0 || table.capacity >= reservation.party_size
+    };
+
+    match &reservation.table {
+        Some(name) => {
+            match tables.iter().find(|table| &table.name == name) {
+                None => conflicts.push("Table does not exist!".to_string()),
+                Some(table) if !fits(table) => conflicts.push(format!(
+                    "`{}` only seats {}.", table.name, table.capacity
+                )),
+                Some(_) => ()
+            }
+            for other in others.iter().filter(|other| other.table.as_ref() == Some(name)) {
+                conflicts.push(format!(
+                    "`{}` is booked by {} at {:02}:{:02} for {} minutes.",
+                    name, other.guest, other.start.hour, other.start.minute, other.duration
+                ));
+            }
+        }
+        None => {
+            let in_zone = |table: &&dbt::VirtualTable| {
+                reservation.zone.as_ref().is_none_or(|zone| &table.zone == zone)
+            };
+
+            if tables.iter().filter(in_zone).count() == 0 {
+                conflicts.push("Zone has no tables.".to_string());
+                return Ok(conflicts)
+            }
+
+            let free = tables
+                .iter()
+                .filter(in_zone)
+                .filter(|table| fits(table))
+                .filter(|table| {
+                    !others.iter().any(|other| other.table.as_ref() == Some(&table.name))
+                })
+                .count();
+            let zone_bookings = others
+                .iter()
+                .filter(|other| {
+                    other.table.is_none()
+                    && (other.zone.is_none() || reservation.zone.is_none() || other.zone == reservation.zone)
+                })
+                .count();
+
+            if free <= zone_bookings {
+                conflicts.push(format!(
+                    "No table for {} is left{} at that time.",
+                    reservation.party_size,
+                    match &reservation.zone {
+                        Some(zone) => format!(" in `{}`", zone),
+                        None => String::new()
+                    }
+                ));
+            }
+        }
+    }
+
+    Ok(conflicts)
+
+}
@@ -0,0 +1,63 @@
+use crate::db::DatabaseElement;
+use crate::shared::dbt as dbt;
+
+/// Whether the table is in the section of the staff member, on its
+/// own or through its zone.
+pub fn is_assigned(staff: &dbt::Staff, table: &dbt::VirtualTable) -> bool {
+    staff.tables.contains(&table.name)
+    || (!table.zone.is_empty() && staff.zones.contains(&table.zone))
+}
+
+/// Tables in the section of the staff member, in the order they are
+/// listed in.
+pub fn tables_of(staff: &dbt::Staff, db: &sled::Db) -> Result<Vec<dbt::VirtualTable>, String> {
+
+    let mut tables = dbt::VirtualTable::get_all(db)?
+        .into_iter()
+        .filter(|table| is_assigned(staff, table))
+        .collect::<Vec<dbt::VirtualTable>>();
+
+    tables.sort_by(|a, b| (a.position, &a.name).cmp(&(b.position, &b.name)));
+
+    Ok(tables)
+
+}
+
+/// Unpaid orders at the tables of the staff member's section and the
+/// ones they accepted at other tables.
+pub fn open_orders_of(staff: &dbt::Staff, db: &sled::Db) -> Result<Vec<dbt::Order>, String> {
+
+    let tables = tables_of(staff, db)?;
+
+    let mut orders = dbt::Order::get_all(db)?
+        .into_iter()
+        .filter(|order| {
+            !order.paid
+            && (
+                tables.iter().any(|table| table.name == order.id.table)
+                || order.accepted_by.as_ref() == Some(&staff.name)
+            )
+        })
+        .collect::<Vec<dbt::Order>>();
+
+    orders.sort_by(|a, b| (&a.id.table, a.id.count).cmp(&(&b.id.table, b.id.count)));
+
+    Ok(orders)
+
+}
+
+/// Why the section can't be assigned, if it can't.
+pub fn check(tables: &[dbt::VirtualTableID], zones: &[String], db: &sled::Db) -> Result<Result<(), String>, String> {
+
+    let existing = dbt::VirtualTable::get_all(db)?;
+
+    if let Some(missing) = tables.iter().find(|name| !existing.iter().any(|table| &table.name == *name)) {
+        return Ok(Err(format!("Table `{}` does not exist!", missing)))
+    }
+    if let Some(missing) = zones.iter().find(|zone| !existing.iter().any(|table| &table.zone == *zone)) {
+        return Ok(Err(format!("Zone `{}` has no tables.", missing)))
+    }
+
+    Ok(Ok(()))
+
+}
@@ -0,0 +1,122 @@
+use crate::clock;
+use crate::db::DatabaseElement;
+use crate::shared::dbt as dbt;
+use crate::shared::req_resp as req;
+use crate::shared::totals;
+
+/// The session of the guests currently at the table, if any.
+pub fn open_session_of_table(
+    table: &dbt::VirtualTableID,
+    db: &sled::Db
+) -> Result<Option<dbt::Session>, String> {
+
+    Ok(
+        dbt::Session {
+            table: table.clone(),
+            closed: false,
+            ..Default::default()
+        }
+        .get_templated(db)?
+        .into_iter()
+        .find(|session| &session.table == table)
+    )
+
+}
+
+pub fn session_by_id(id: dbt::SessionID, db: &sled::Db) -> Result<Option<dbt::Session>, String> {
+
+    Ok(
+        dbt::Session::get_all(db)?
+            .into_iter()
+            .find(|session| session.id == id)
+    )
+
+}
+
+/// The open session of the table, a new one is opened if there is
+/// none so that the first order of a party starts its session.
+pub fn session_for_order(table: &dbt::VirtualTableID, db: &sled::Db) -> Result<dbt::Session, String> {
+
+    if let Some(session) = open_session_of_table(table, db)? {
+        return Ok(session)
+    }
+
+    let session = dbt::Session {
+        id: db.generate_id().map_err(|err| err.to_string())?,
+        table: table.clone(),
+        closed: false,
+        opened_at: clock::timestamp(),
+        closed_at: None
+    };
+
+    session.insert(db)?;
+
+    Ok(session)
+
+}
+
+pub fn orders_of_session(id: dbt::SessionID, db: &sled::Db) -> Result<Vec<dbt::Order>, String> {
+
+    Ok(
+        dbt::Order::get_all(db)?
+            .into_iter()
+            .filter(|order| order.session == Some(id))
+            .collect()
+    )
+
+}
+
+/// Ids of all open sessions.
+pub fn open_sessions(db: &sled::Db) -> Result<Vec<dbt::SessionID>, String> {
+
+    Ok(
+        dbt::Session {
+            closed: false,
+            ..Default::default()
+        }
+        .get_status(db)?
+        .into_iter()
+        .map(|session| session.id)
+        .collect()
+    )
+
+}
+
+pub fn summarize(session: dbt::Session, db: &sled::Db) -> Result<req::SessionsSpecificResponseData, String> {
+
+    let orders = orders_of_session(session.id, db)?;
+    let total = orders.iter()
+        .map(|order| totals::order_totals(order).total.cents())
+        .sum();
+
+    Ok(req::SessionsSpecificResponseData {
+        session,
+        orders: orders.into_iter().map(req::TotaledOrder::from).collect(),
+        total: dbt::Amount::from_cents(total)
+    })
+
+}
+
+/// Closes the open session of the table once all of its orders are
+/// paid, returns whether it did.
+pub fn close_if_settled(table: &dbt::VirtualTableID, db: &sled::Db) -> Result<bool, String> {
+
+    let session = match open_session_of_table(table, db)? {
+        Some(session) => session,
+        None => return Ok(false)
+    };
+
+    if orders_of_session(session.id, db)?.iter().any(|order| !order.paid) {
+        return Ok(false)
+    }
+
+    session.remove(db)?;
+
+    let mut session = session;
+    session.closed = true;
+    session.closed_at = Some(clock::timestamp());
+    session.insert(db)?;
+
+    Ok(true)
+
+}
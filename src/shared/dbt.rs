@@ -1,9 +1,28 @@
+use std::collections::BTreeMap;
+
 use serde::{Deserialize, Serialize};
 
+// Records are stored with bincode, which writes every field in order
+// and can't tell that one is missing. The `#[serde(default)]`s below
+// only let JSON bodies leave fields out, changing the fields of a
+// stored type needs a new schema version in `crate::migrate`.
+
 pub type VirtualTableID = String;
 pub type OfferID        = String;
+pub type BillID         = u64;
+pub type PaymentID      = u64;
+pub type CategoryID     = String;
+pub type TaxRateID      = String;
+pub type StockItemID = String;
+pub type PromotionID = String;
+pub type SessionID = u64;
+pub type ReservationID = u64;
+pub type StaffID = String;
+pub type StationID = String;
+pub type TicketID = u64;
+pub type AlertID = u64;
 
-#[derive(Debug, Serialize, Deserialize, Default, Clone)]
+#[derive(Debug, Serialize, Deserialize, Default, Clone, PartialEq, Eq, Hash)]
 pub struct OrderID {
     pub table: VirtualTableID,
     pub count: u32
@@ -12,20 +31,213 @@ pub struct OrderID {
 #[derive(Debug, Serialize, Deserialize, Default, Clone)]
 pub struct VirtualTable {
     pub name: VirtualTableID,
+    /// Kept by the server like the state and the generation, the
+    /// ones sent when a table is created or edited are ignored.
+    #[serde(default)]
     pub order_count: u32,
+    /// Part of the venue the table is in (terrace, inside, bar, ...).
+    #[serde(default)]
+    pub zone: String,
+    /// Number of seats.
+    #[serde(default)]
+    pub capacity: u32,
+    /// Tables are listed from the lowest position up.
+    #[serde(default)]
+    pub position: u32,
+    #[serde(default)]
+    pub state: TableState,
+    /// Bumped to revoke the guest links of the table handed out so
+    /// far, see [`crate::guest`].
+    #[serde(default)]
+    pub token_generation: u32,
+}
+
+#[derive(Debug, Serialize, Deserialize, Default, Clone, Copy, PartialEq, Eq)]
+pub enum TableState {
+    #[default]
+    Free,
+    /// Set when an order comes in for a free table.
+    Occupied,
+    NeedsAttention,
+    /// Set when the table's bill is closed.
+    NeedsCleaning,
 }
 
 #[derive(Debug, Serialize, Deserialize, Default, Clone)]
 pub struct OrderItem {
     pub id: OfferID,
     pub count: u32,
+    pub note: Option<String>,
+    /// Snapshot of the offer at the time of ordering, filled in by
+    /// the server so that later changes to the offer don't change
+    /// what the order is worth.
+    #[serde(default)]
+    pub name: String,
+    #[serde(default)]
+    pub price_integer: u32,
+    #[serde(default)]
+    pub price_fraction: u32,
+    pub tax: Option<TaxRate>,
+    /// Promotions applied when the order was placed, filled in by
+    /// the server like the price.
+    #[serde(default)]
+    pub discounts: Vec<AppliedDiscount>,
+}
+
+/// How much a promotion took off an order line in total.
+#[derive(Debug, Serialize, Deserialize, Default, Clone, PartialEq, Eq)]
+pub struct AppliedDiscount {
+    pub promotion: PromotionID,
+    pub amount: Amount,
 }
 
 #[derive(Debug, Serialize, Deserialize, Default, Clone)]
 pub struct Order {
     pub id: OrderID,
     pub finished: bool,
-    pub items: Vec<OrderItem>
+    pub items: Vec<OrderItem>,
+    pub note: Option<String>,
+    /// Set once the bill containing the order is closed.
+    #[serde(default)]
+    pub paid: bool,
+    /// Codes that unlock promotions, e.g. the staff discount.
+    #[serde(default)]
+    pub codes: Vec<String>,
+    /// Session of the party that placed the order, set by the server.
+    #[serde(default)]
+    pub session: Option<SessionID>,
+    /// Staff member who placed or accepted the order, set by the
+    /// server.
+    #[serde(default)]
+    pub accepted_by: Option<StaffID>,
+    /// Staff member who finished the order, set by the server.
+    #[serde(default)]
+    pub served_by: Option<StaffID>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Default, Clone, Copy, PartialEq, Eq)]
+pub enum ReservationStatus {
+    #[default]
+    Booked,
+    Arrived,
+    NoShow,
+    Cancelled,
+}
+
+impl ReservationStatus {
+
+    /// Whether the reservation still holds its table.
+    pub fn is_active(&self) -> bool {
+        matches!(self, ReservationStatus::Booked | ReservationStatus::Arrived)
+    }
+
+}
+
+/// A booking for a table, or for any table of a zone.
+#[derive(Debug, Serialize, Deserialize, Default, Clone)]
+pub struct Reservation {
+    /// Assigned by the server when the reservation is booked.
+    #[serde(default)]
+    pub id:         ReservationID,
+    pub guest:      String,
+    pub contact:    String,
+    pub party_size: u32,
+    /// Local date in the `YYYY-MM-DD` format.
+    pub date:       String,
+    pub start:      TimeOfDay,
+    /// In minutes.
+    pub duration:   u32,
+    /// A specific table, or `None` to take any fitting table of the
+    /// zone (or of the whole venue if there is no zone either).
+    pub table:      Option<VirtualTableID>,
+    pub zone:       Option<String>,
+    pub note:       Option<String>,
+    #[serde(default)]
+    pub status:     ReservationStatus,
+}
+
+/// A party at a table, from their first order until their bill is
+/// paid.
+#[derive(Debug, Serialize, Deserialize, Default, Clone)]
+pub struct Session {
+    pub id: SessionID,
+    pub table: VirtualTableID,
+    pub closed: bool,
+    pub opened_at: i64,
+    pub closed_at: Option<i64>,
+}
+
+/// Where orders are prepared, e.g. the bar or the kitchen.
+#[derive(Debug, Serialize, Deserialize, Default, Clone)]
+pub struct Station {
+    pub name: StationID,
+}
+
+#[derive(Debug, Serialize, Deserialize, Default, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum TicketStatus {
+    #[default]
+    Queued,
+    Preparing,
+    Ready,
+    Served,
+}
+
+impl TicketStatus {
+
+    /// The status after this one, `None` once served.
+    pub fn next(&self) -> Option<TicketStatus> {
+        match self {
+            TicketStatus::Queued => Some(TicketStatus::Preparing),
+            TicketStatus::Preparing => Some(TicketStatus::Ready),
+            TicketStatus::Ready => Some(TicketStatus::Served),
+            TicketStatus::Served => None,
+        }
+    }
+
+}
+
+#[derive(Debug, Serialize, Deserialize, Default, Clone)]
+pub struct TicketLine {
+    /// Index of the line in the order.
+    pub line:   usize,
+    pub name:   String,
+    pub count:  u32,
+    pub note:   Option<String>,
+    pub status: TicketStatus,
+}
+
+/// The lines of an order a single station prepares.
+#[derive(Debug, Serialize, Deserialize, Default, Clone)]
+pub struct Ticket {
+    pub id:         TicketID,
+    pub order:      OrderID,
+    pub station:    StationID,
+    pub lines:      Vec<TicketLine>,
+    pub note:       Option<String>,
+    /// The least advanced status of the lines.
+    pub status:     TicketStatus,
+    pub created_at: i64,
+    pub updated_at: i64,
+}
+
+/// What the guests at a table want from the staff.
+#[derive(Debug, Serialize, Deserialize, Default, Clone, Copy, PartialEq, Eq)]
+pub enum AlertKind {
+    #[default]
+    CallWaiter,
+    RequestBill(PaymentMethod),
+}
+
+/// Raised from the guest page, open until a staff member
+/// acknowledges it.
+#[derive(Debug, Serialize, Deserialize, Default, Clone)]
+pub struct Alert {
+    pub id:              AlertID,
+    pub table:           VirtualTableID,
+    pub kind:            AlertKind,
+    pub created_at:      i64,
+    pub acknowledged_by: Option<StaffID>,
+    pub acknowledged_at: Option<i64>,
 }
 
 #[derive(Debug, Serialize, Deserialize, Default, Clone)]
@@ -34,4 +246,633 @@ pub struct Offer {
     pub description:    String,
     pub price_integer:  u32,
     pub price_fraction: u32,
-}
\ No newline at end of file
+    /// Manually toggled by staff, a sold out offer is never available.
+    #[serde(default)]
+    pub sold_out:       bool,
+    /// Weekly windows in which the offer is available, an empty
+    /// schedule means the offer is available all the time.
+    #[serde(default)]
+    pub schedule:       Vec<AvailabilityWindow>,
+    pub category:       Option<CategoryID>,
+    /// Overrides the tax rate of the category.
+    pub tax_rate:       Option<TaxRateID>,
+    /// Stock used up by one piece of the offer.
+    #[serde(default)]
+    pub recipe:         Vec<RecipeItem>,
+    /// Kept up to date by the inventory, set while a stock item of
+    /// the recipe is at or below its low stock threshold.
+    #[serde(default)]
+    pub out_of_stock:   bool,
+    /// Overrides the station of the category.
+    #[serde(default)]
+    pub station:        Option<StationID>,
+    /// Allergens the offer contains, to be disclosed to guests.
+    #[serde(default)]
+    pub allergens:      Vec<Allergen>,
+    #[serde(default)]
+    pub dietary:        Vec<DietaryTag>,
+    /// Name and description in other languages than the venue's,
+    /// keyed by language code (`en`, `de`, ...).
+    #[serde(default)]
+    pub translations:   BTreeMap<String, OfferTranslation>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Default, Clone, PartialEq, Eq)]
+pub struct OfferTranslation {
+    pub name:        String,
+    pub description: String,
+}
+
+/// The 14 allergens that have to be disclosed under EU Regulation
+/// 1169/2011.
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, strum::EnumString)]
+pub enum Allergen {
+    /// Cereals containing gluten.
+    Gluten,
+    Crustaceans,
+    Eggs,
+    Fish,
+    Peanuts,
+    Soybeans,
+    Milk,
+    /// Tree nuts.
+    Nuts,
+    Celery,
+    Mustard,
+    Sesame,
+    /// Sulphur dioxide and sulphites.
+    Sulphites,
+    Lupin,
+    Molluscs,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, strum::EnumString)]
+pub enum DietaryTag {
+    Vegetarian,
+    Vegan,
+    GlutenFree,
+    LactoseFree,
+    Halal,
+}
+
+#[derive(Debug, Serialize, Deserialize, Default, Clone, PartialEq, Eq)]
+pub struct RecipeItem {
+    pub stock:    StockItemID,
+    pub quantity: i64,
+}
+
+/// Something that is kept in stock, counted in whole `unit`s
+/// (`g`, `ml`, `pcs`, ...).
+#[derive(Debug, Serialize, Deserialize, Default, Clone)]
+pub struct StockItem {
+    pub name:          StockItemID,
+    pub unit:          String,
+    /// Can go below zero if more was sold than was counted in.
+    pub quantity:      i64,
+    /// Offers that use the item are sold out once the quantity
+    /// drops to this.
+    #[serde(default)]
+    pub low_threshold: i64,
+}
+
+#[derive(Debug, Serialize, Deserialize, Default, Clone)]
+pub struct Category {
+    pub name:     CategoryID,
+    /// Tax rate of every offer in the category that doesn't set
+    /// its own.
+    pub tax_rate: Option<TaxRateID>,
+    /// Station preparing the offers of the category that don't set
+    /// their own.
+    #[serde(default)]
+    pub station:  Option<StationID>,
+    /// Name in other languages than the venue's, keyed by language
+    /// code.
+    #[serde(default)]
+    pub translations: BTreeMap<String, String>,
+}
+
+/// A tax (PDV) rate, prices of offers include the tax.
+#[derive(Debug, Serialize, Deserialize, Default, Clone, PartialEq, Eq)]
+pub struct TaxRate {
+    pub name:         TaxRateID,
+    /// The rate in hundredths of a percent, 25% is `2500`.
+    pub basis_points: u32,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+pub enum Weekday {
+    Monday,
+    Tuesday,
+    Wednesday,
+    Thursday,
+    Friday,
+    Saturday,
+    Sunday,
+}
+
+impl Weekday {
+
+    pub fn previous(&self) -> Weekday {
+        match self {
+            Weekday::Monday    => Weekday::Sunday,
+            Weekday::Tuesday   => Weekday::Monday,
+            Weekday::Wednesday => Weekday::Tuesday,
+            Weekday::Thursday  => Weekday::Wednesday,
+            Weekday::Friday    => Weekday::Thursday,
+            Weekday::Saturday  => Weekday::Friday,
+            Weekday::Sunday    => Weekday::Saturday,
+        }
+    }
+
+}
+
+#[derive(Debug, Serialize, Deserialize, Default, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+#[serde(try_from = "UncheckedTimeOfDay")]
+pub struct TimeOfDay {
+    pub hour:   u8,
+    pub minute: u8,
+}
+
+/// A [`TimeOfDay`] as it is sent, before the hour and minute are
+/// checked.
+#[derive(Deserialize)]
+struct UncheckedTimeOfDay {
+    hour:   u8,
+    minute: u8,
+}
+
+impl TryFrom<UncheckedTimeOfDay> for TimeOfDay {
+    type Error = String;
+    fn try_from(time: UncheckedTimeOfDay) -> Result<Self, Self::Error> {
+        if time.hour >= 24 || time.minute >= 60 {
+            return Err(format!("{:02}:{:02} is not a time of day.", time.hour, time.minute))
+        }
+        Ok(TimeOfDay {
+            hour: time.hour,
+            minute: time.minute
+        })
+    }
+}
+
+/// A time window repeating on the given days of every week.
+///
+/// If `end` is not after `start` the window goes over midnight,
+/// e.g. a Friday window from 22:00 to 02:00 also covers the first
+/// two hours of Saturday.
+#[derive(Debug, Serialize, Deserialize, Default, Clone)]
+pub struct AvailabilityWindow {
+    pub days:  Vec<Weekday>,
+    pub start: TimeOfDay,
+    pub end:   TimeOfDay,
+}
+
+impl AvailabilityWindow {
+
+    pub fn contains(&self, day: Weekday, time: TimeOfDay) -> bool {
+        if self.start < self.end {
+            self.days.contains(&day) && self.start <= time && time < self.end
+        } else {
+            (self.days.contains(&day) && self.start <= time)
+            || (self.days.contains(&day.previous()) && time < self.end)
+        }
+    }
+
+}
+
+impl Offer {
+
+    pub fn is_available_at(&self, day: Weekday, time: TimeOfDay) -> bool {
+        !self.sold_out && !self.out_of_stock && (
+            self.schedule.is_empty()
+            || self.schedule.iter().any(|window| window.contains(day, time))
+        )
+    }
+
+    /// Whether the offer has all of the `diet` tags and none of the
+    /// `exclude` allergens. Vegan offers are vegetarian too.
+    pub fn suits(&self, diet: &[DietaryTag], exclude: &[Allergen]) -> bool {
+        diet.iter().all(|tag| {
+            self.dietary.contains(tag)
+            || (*tag == DietaryTag::Vegetarian && self.dietary.contains(&DietaryTag::Vegan))
+        })
+        && !self.allergens.iter().any(|allergen| exclude.contains(allergen))
+    }
+
+}
+
+/// What a promotion applies to.
+#[derive(Debug, Serialize, Deserialize, Default, Clone, PartialEq, Eq)]
+pub enum PromotionScope {
+    Offer { offer: OfferID },
+    Category { category: CategoryID },
+    /// Every line of the order.
+    #[default]
+    Order,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+pub enum PromotionRule {
+    /// Takes a share of the price off, 20% is `2000`.
+    Percentage { basis_points: u32 },
+    /// Takes a fixed amount off every piece, or off the whole order
+    /// once if the promotion is for the whole order.
+    Fixed { amount: Amount },
+    /// Of every `buy + free` pieces, `free` of the cheapest are free.
+    BuyGetFree { buy: u32, free: u32 },
+}
+
+impl Default for PromotionRule {
+    fn default() -> Self {
+        PromotionRule::Percentage { basis_points: 0 }
+    }
+}
+
+/// A price adjustment applied automatically to orders when they
+/// are placed.
+#[derive(Debug, Serialize, Deserialize, Default, Clone)]
+pub struct Promotion {
+    pub name:     PromotionID,
+    pub scope:    PromotionScope,
+    pub rule:     PromotionRule,
+    /// Windows in which the promotion runs, always if empty.
+    #[serde(default)]
+    pub schedule: Vec<AvailabilityWindow>,
+    /// Only applies to orders placed with this code.
+    pub code:     Option<String>,
+}
+
+impl Promotion {
+
+    pub fn is_active_at(&self, day: Weekday, time: TimeOfDay) -> bool {
+        self.schedule.is_empty()
+        || self.schedule.iter().any(|window| window.contains(day, time))
+    }
+
+}
+
+/// An amount of money split the same way offers split their price.
+#[derive(Debug, Serialize, Deserialize, Default, Clone, Copy, PartialEq, Eq)]
+#[serde(try_from = "UncheckedAmount")]
+pub struct Amount {
+    pub integer:  u32,
+    pub fraction: u32,
+}
+
+/// An [`Amount`] as it is sent, before the fraction is checked.
+#[derive(Deserialize)]
+struct UncheckedAmount {
+    integer:  u32,
+    fraction: u32,
+}
+
+impl TryFrom<UncheckedAmount> for Amount {
+    type Error = String;
+    fn try_from(amount: UncheckedAmount) -> Result<Self, Self::Error> {
+        if amount.fraction >= 100 {
+            return Err(format!("Fraction of an amount has to be below 100, not {}.", amount.fraction))
+        }
+        Ok(Amount {
+            integer: amount.integer,
+            fraction: amount.fraction
+        })
+    }
+}
+
+impl Amount {
+
+    pub fn from_cents(cents: u64) -> Self {
+        Amount {
+            integer: (cents / 100) as u32,
+            fraction: (cents % 100) as u32
+        }
+    }
+
+    pub fn cents(&self) -> u64 {
+        self.integer as u64 * 100 + self.fraction as u64
+    }
+
+}
+
+impl std::fmt::Display for Amount {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}.{:02}", self.integer, self.fraction)
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Default, Clone)]
+pub struct LineTotal {
+    pub id: OfferID,
+    pub name: String,
+    pub count: u32,
+    pub unit: Amount,
+    /// Taken off by promotions, already subtracted from `total`.
+    pub discount: Amount,
+    pub total: Amount,
+    pub tax: Option<TaxRate>,
+}
+
+/// How much of a gross amount is tax for a single tax rate.
+#[derive(Debug, Serialize, Deserialize, Default, Clone)]
+pub struct TaxBreakdown {
+    /// `None` for lines that were ordered without a tax rate.
+    pub rate: Option<TaxRate>,
+    pub net: Amount,
+    pub tax: Amount,
+    pub gross: Amount,
+}
+
+#[derive(Debug, Serialize, Deserialize, Default, Clone)]
+pub struct OrderTotals {
+    pub lines: Vec<LineTotal>,
+    pub taxes: Vec<TaxBreakdown>,
+    pub total: Amount,
+}
+
+#[derive(Debug, Serialize, Deserialize, Default, Clone)]
+pub struct TableTotals {
+    pub table: VirtualTableID,
+    pub order_count: u32,
+    pub total: Amount,
+}
+
+/// A bill groups the unpaid orders of a table so they can be paid,
+/// possibly in more than one [`Payment`].
+#[derive(Debug, Serialize, Deserialize, Default, Clone)]
+pub struct Bill {
+    pub id: BillID,
+    pub table: VirtualTableID,
+    pub orders: Vec<OrderID>,
+    pub closed: bool,
+    /// Unix timestamps in seconds.
+    pub opened_at: i64,
+    pub closed_at: Option<i64>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Default, Clone, Copy, PartialEq, Eq)]
+pub enum PaymentMethod {
+    #[default]
+    Cash,
+    Card,
+}
+
+/// Part of an order line that is being paid for when splitting a
+/// bill by items, `line` is the index into the order's `items`.
+#[derive(Debug, Serialize, Deserialize, Default, Clone)]
+pub struct PaidItem {
+    pub order: OrderID,
+    pub line: usize,
+    pub count: u32,
+}
+
+#[derive(Debug, Serialize, Deserialize, Default, Clone)]
+pub struct Payment {
+    pub id: PaymentID,
+    pub bill: BillID,
+    pub method: PaymentMethod,
+    /// What this payment takes off the bill, tips not included.
+    pub amount: Amount,
+    pub tip: Amount,
+    /// Cash handed over by the guest, if known.
+    pub tendered: Option<Amount>,
+    pub change: Amount,
+    /// Empty unless the payment is for specific items.
+    pub items: Vec<PaidItem>,
+    /// Unix timestamp in seconds.
+    pub created_at: i64,
+}
+
+/// Key the guest links of the tables are signed with, generated on
+/// first use.
+#[derive(Debug, Serialize, Deserialize, Default, Clone)]
+pub struct SigningKey {
+    pub key: Vec<u8>,
+}
+
+/// Details about the venue printed at the top of every receipt.
+#[derive(Debug, Serialize, Deserialize, Default, Clone)]
+pub struct Venue {
+    pub name:    String,
+    pub address: String,
+    /// Croatian personal identification number (OIB) of the owner.
+    pub oib:     String,
+    /// Printed at the bottom of every receipt.
+    pub footer:  Option<String>,
+    /// Language code of the names and descriptions of the menu,
+    /// translations are in addition to it.
+    #[serde(default)]
+    pub language: String,
+}
+
+/// Receipts are numbered from 1 again at the start of every year.
+#[derive(Debug, Serialize, Deserialize, Default, Clone, PartialEq, Eq)]
+pub struct ReceiptID {
+    pub year:   i32,
+    pub number: u32,
+}
+
+impl std::fmt::Display for ReceiptID {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}-{}", self.year, self.number)
+    }
+}
+
+/// Everything that was printed on a receipt, stored as it was issued
+/// so it can be printed again later.
+#[derive(Debug, Serialize, Deserialize, Default, Clone)]
+pub struct Receipt {
+    pub id:        ReceiptID,
+    /// Unix timestamp in seconds.
+    pub issued_at: i64,
+    pub venue:     Venue,
+    pub table:     VirtualTableID,
+    pub bill:      Option<BillID>,
+    pub orders:    Vec<OrderID>,
+    pub lines:     Vec<LineTotal>,
+    pub taxes:     Vec<TaxBreakdown>,
+    pub total:     Amount,
+    pub payments:  Vec<Payment>,
+    /// Set if fiscalization was enabled when the receipt was issued.
+    pub fiscal:    Option<FiscalData>,
+}
+
+/// Where fiscal requests are sent to, only the local mock of the tax
+/// authority's fiscalization service (CIS) exists for now.
+#[derive(Debug, Serialize, Deserialize, Default, Clone, Copy, PartialEq, Eq)]
+pub enum FiscalTransport {
+    #[default]
+    MockCis,
+    /// A mock CIS that can't be reached, for testing the retries.
+    MockCisOffline,
+}
+
+#[derive(Debug, Serialize, Deserialize, Default, Clone)]
+pub struct FiscalSettings {
+    pub enabled:       bool,
+    pub in_vat_system: bool,
+    /// Code of the business premises (oznaka poslovnog prostora).
+    pub premises:      String,
+    /// Code of the billing device (oznaka naplatnog uređaja).
+    pub device:        String,
+    /// OIB of the staff member issuing the receipts.
+    pub operator_oib:  String,
+    /// Path to the PEM encoded private key of the FINA certificate.
+    pub key_path:      String,
+    pub transport:     FiscalTransport,
+}
+
+/// Fiscal state of a receipt.
+///
+/// The ZKI (protective code of the issuer) is computed locally when
+/// the receipt is issued, the JIR (unique receipt identifier) is
+/// assigned by the tax authority and stays `None` until a request
+/// went through.
+#[derive(Debug, Serialize, Deserialize, Default, Clone)]
+pub struct FiscalData {
+    pub premises:      String,
+    pub device:        String,
+    pub operator_oib:  String,
+    pub in_vat_system: bool,
+    pub zki:           Option<String>,
+    pub jir:           Option<String>,
+    pub attempts:      u32,
+    pub last_error:    Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Default, Clone, Copy, PartialEq, Eq)]
+pub enum Role {
+    #[default]
+    Waiter,
+    Kitchen,
+    /// Has [`crate::shared::req_resp::Authority::Admin`], which
+    /// grants every permission.
+    Manager,
+}
+
+/// What a staff member is allowed to do, on top of looking at the
+/// orders, tables and bills which every staff member can.
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+pub enum Permission {
+    TakeOrders,
+    ServeOrders,
+    HandlePayments,
+    ManageReservations,
+    ManageTables,
+    ManageMenu,
+    ManageStock,
+    ManageSettings,
+    ViewReports,
+    ManageStaff,
+}
+
+#[derive(Debug, Serialize, Deserialize, Default, Clone)]
+pub struct Staff {
+    /// Login name.
+    pub name:          StaffID,
+    pub role:          Role,
+    /// Granted on top of the permissions of the role.
+    #[serde(default)]
+    pub permissions:   Vec<Permission>,
+    /// Argon2 hash of the PIN or password in the PHC string format,
+    /// never sent out.
+    pub secret_hash:   String,
+    #[serde(default)]
+    pub disabled:      bool,
+    /// Failed logins in a row, reset on a successful one.
+    #[serde(default)]
+    pub failed_logins: u32,
+    #[serde(default)]
+    pub locked_until:  Option<i64>,
+    /// Section of the staff member, tables assigned one by one and
+    /// whole zones.
+    #[serde(default)]
+    pub tables:        Vec<VirtualTableID>,
+    #[serde(default)]
+    pub zones:         Vec<String>,
+}
+
+/// Bearer token handed out on login.
+#[derive(Debug, Serialize, Deserialize, Default, Clone)]
+pub struct StaffToken {
+    pub token:      String,
+    pub staff:      StaffID,
+    pub expires_at: i64,
+}
+
+/// Largest amount of a single offer that can be ordered in one order.
+pub const ORDER_ITEM_MAX_COUNT: u32 = 50;
+
+/// Maximum number of characters a note on an [`Order`] or
+/// [`OrderItem`] may have after being sanitized.
+pub const NOTE_MAX_LENGTH: usize = 200;
+
+/// Cleans up a free-text note before it gets stored.
+///
+/// Control characters (newlines, tabs, ...) become spaces, runs of
+/// whitespace are collapsed and the result is trimmed. A note that ends
+/// up empty is treated as no note at all.
+///
+/// Returns an error if the cleaned up note is longer than
+/// [`NOTE_MAX_LENGTH`] characters.
+pub fn sanitize_note(note: Option<String>) -> Result<Option<String>, String> {
+
+    let note = match note {
+        Some(note) => note,
+        None => return Ok(None)
+    };
+
+    let cleaned = note
+        .chars()
+        .map(|c| if c.is_control() {' '} else {c})
+        .collect::<String>()
+        .split_whitespace()
+        .collect::<Vec<&str>>()
+        .join(" ");
+
+    if cleaned.is_empty() {
+        return Ok(None)
+    }
+
+    let length = cleaned.chars().count();
+    if length > NOTE_MAX_LENGTH {
+        return Err(format!(
+            "Note is {} characters long, the limit is {}.",
+            length, NOTE_MAX_LENGTH
+        ))
+    }
+
+    Ok(Some(cleaned))
+
+}
+
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    #[test]
+    fn amounts_with_a_fraction_of_100_or_more_are_rejected() {
+
+        assert!(serde_json::from_str::<Amount>(r#"{"integer": 1, "fraction": 99}"#).is_ok());
+        assert!(serde_json::from_str::<Amount>(r#"{"integer": 1, "fraction": 100}"#).is_err());
+
+        let amount = Amount { integer: 3, fraction: 5 };
+        let stored = bincode::serialize(&amount).unwrap();
+        assert_eq!(bincode::deserialize::<Amount>(&stored).unwrap(), amount);
+
+    }
+
+    #[test]
+    fn times_past_23_59_are_rejected() {
+
+        assert!(serde_json::from_str::<TimeOfDay>(r#"{"hour": 23, "minute": 59}"#).is_ok());
+        assert!(serde_json::from_str::<TimeOfDay>(r#"{"hour": 24, "minute": 0}"#).is_err());
+        assert!(serde_json::from_str::<TimeOfDay>(r#"{"hour": 12, "minute": 60}"#).is_err());
+
+        let time = TimeOfDay { hour: 7, minute: 30 };
+        let stored = bincode::serialize(&time).unwrap();
+        assert_eq!(bincode::deserialize::<TimeOfDay>(&stored).unwrap(), time);
+
+    }
+
+}
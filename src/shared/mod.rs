@@ -1,3 +1,4 @@
 pub mod dbt;
 pub mod logging;
-pub mod req_resp;
\ No newline at end of file
+pub mod req_resp;
+pub mod totals;
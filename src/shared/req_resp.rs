@@ -9,6 +9,7 @@ use tokio::net::TcpStream;
 use tokio::io::{AsyncReadExt, AsyncWriteExt, ReadBuf};
 
 use crate::shared::dbt;
+use crate::shared::totals;
 
 use super::dbt::VirtualTableID;
 
@@ -36,8 +37,12 @@ pub fn get_local_ip_address() -> Result<String, std::io::Error> {
 //////////////////////////////////////////////////
 // Tables
 
+    /// Query of `GET /tables`, both filters are optional.
     #[derive(Serialize, Deserialize)]
-    pub struct TablesRequestData;
+    pub struct TablesRequestData {
+        pub zone: Option<String>,
+        pub state: Option<dbt::TableState>
+    }
 #[derive(Serialize, Deserialize)]
 pub struct TablesResponseData {
     pub tables: Vec<dbt::VirtualTable>
@@ -65,6 +70,90 @@ pub struct TablesInsertResponseData;
 #[derive(Serialize, Deserialize)]
 pub struct TablesDeleteResponseData;
 
+
+    #[derive(Serialize, Deserialize)]
+    pub struct TablesStateRequestData {
+        pub table: VirtualTableID,
+        pub state: dbt::TableState
+    }
+
+
+/// Guest link of a table.
+#[derive(Serialize, Deserialize)]
+pub struct TablesTokenResponseData {
+    pub table: VirtualTableID,
+    pub token: String,
+    pub url: String
+}
+
+
+    /// Revokes the guest links of the table handed out so far.
+    #[derive(Serialize, Deserialize)]
+    pub struct TablesTokenRotateRequestData {
+        pub table: VirtualTableID
+    }
+
+
+    /// Query of the guest page.
+    #[derive(Serialize, Deserialize)]
+    pub struct GuestPageRequestData {
+        pub token: Option<String>
+    }
+
+/// How far along an order is, as the guests see it.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum GuestOrderStatus {
+    Received,
+    Preparing,
+    Ready,
+    Served
+}
+
+impl From<dbt::TicketStatus> for GuestOrderStatus {
+    fn from(status: dbt::TicketStatus) -> Self {
+        match status {
+            dbt::TicketStatus::Queued => GuestOrderStatus::Received,
+            dbt::TicketStatus::Preparing => GuestOrderStatus::Preparing,
+            dbt::TicketStatus::Ready => GuestOrderStatus::Ready,
+            dbt::TicketStatus::Served => GuestOrderStatus::Served,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct GuestOrderLine {
+    pub name: String,
+    pub count: u32,
+    pub status: GuestOrderStatus
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct GuestOrder {
+    pub id: dbt::OrderID,
+    pub status: GuestOrderStatus,
+    pub lines: Vec<GuestOrderLine>
+}
+
+/// Orders of the party currently at the table, for the guest page.
+#[derive(Serialize, Deserialize)]
+pub struct TablesOrdersResponseData {
+    pub table: VirtualTableID,
+    pub orders: Vec<GuestOrder>,
+    /// Alerts of the table no one has acknowledged yet.
+    pub alerts: Vec<dbt::AlertKind>
+}
+
+
+#[derive(Serialize, Deserialize)]
+pub struct TablesBillResponseData {
+    pub table: VirtualTableID,
+    pub orders: Vec<TotaledOrder>,
+    /// Lines of all orders merged per offer and unit price.
+    pub lines: Vec<dbt::LineTotal>,
+    pub taxes: Vec<dbt::TaxBreakdown>,
+    pub total: dbt::Amount
+}
+
 //////////////////////////////////////////////////
 // Offers
 
@@ -97,17 +186,191 @@ pub struct OffersInsertResponseData;
 #[derive(Serialize, Deserialize)]
 pub struct OffersDeleteResponseData;
 
+
+    #[derive(Serialize, Deserialize)]
+    pub struct OffersSoldOutRequestData {
+        pub offer: dbt::OfferID,
+        pub sold_out: bool
+    }
+#[derive(Serialize, Deserialize)]
+pub struct OffersSoldOutResponseData {
+    pub offer: dbt::Offer
+}
+
+
+    /// Query of `GET /menu`, comma separated lists of the dietary
+    /// tags every offer has to have and of the allergens none may
+    /// contain, e.g. `?diet=Vegan&exclude=Nuts,Sesame`.
+    #[derive(Serialize, Deserialize)]
+    pub struct MenuRequestData {
+        #[serde(default)]
+        pub diet: String,
+        #[serde(default)]
+        pub exclude: String,
+        /// Language code, takes precedence over `Accept-Language`.
+        #[serde(default)]
+        pub lang: Option<String>
+    }
+
+/// An offer of the menu with its name and description in the language
+/// of the menu, `name` stays the id to order it by.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct MenuOffer {
+    #[serde(flatten)]
+    pub offer: dbt::Offer,
+    pub localized: dbt::OfferTranslation,
+    pub category_name: Option<String>
+}
+
+/// Offers that guests can order right now.
+#[derive(Serialize, Deserialize)]
+pub struct MenuResponseData {
+    /// Language the menu is in.
+    pub language: String,
+    /// Languages the menu can be asked for in.
+    pub languages: Vec<String>,
+    pub offers: Vec<MenuOffer>
+}
+
+//////////////////////////////////////////////////
+// Stations
+
+#[derive(Serialize, Deserialize)]
+pub struct StationsResponseData {
+    pub stations: Vec<dbt::Station>
+}
+
+
+    #[derive(Serialize, Deserialize)]
+    pub struct StationsInsertRequestData {
+        pub station: dbt::Station
+    }
+
+
+    /// Query of `GET /stations-{id}/tickets`, lists the served tickets
+    /// instead of the open ones if set.
+    #[derive(Serialize, Deserialize)]
+    pub struct StationsTicketsRequestData {
+        #[serde(default)]
+        pub served: bool
+    }
+#[derive(Serialize, Deserialize)]
+pub struct StationsTicketsResponseData {
+    pub station: dbt::StationID,
+    pub tickets: Vec<dbt::Ticket>,
+    /// Server time, for working out how long tickets have waited.
+    pub now: i64
+}
+
+
+    /// Moves a ticket line, or the whole ticket if `line` is left out,
+    /// to `status` or else to the next status.
+    #[derive(Serialize, Deserialize)]
+    pub struct TicketsBumpRequestData {
+        pub ticket: dbt::TicketID,
+        pub line: Option<usize>,
+        pub status: Option<dbt::TicketStatus>
+    }
+
+//////////////////////////////////////////////////
+// Alerts
+
+    /// Query of `GET /alerts`, only the alerts of the caller's
+    /// section if `mine` is set.
+    #[derive(Serialize, Deserialize)]
+    pub struct AlertsRequestData {
+        #[serde(default)]
+        pub mine: bool
+    }
+#[derive(Serialize, Deserialize)]
+pub struct AlertsResponseData {
+    pub alerts: Vec<dbt::Alert>
+}
+
+
+    /// Raised by guests, `token` is the one from the guest link of
+    /// the table.
+    #[derive(Serialize, Deserialize)]
+    pub struct AlertsInsertRequestData {
+        pub table: VirtualTableID,
+        pub kind: dbt::AlertKind,
+        pub token: Option<String>
+    }
+#[derive(Serialize, Deserialize)]
+pub struct AlertsInsertResponseData {
+    pub alert: dbt::Alert
+}
+
+
+    #[derive(Serialize, Deserialize)]
+    pub struct AlertsAcknowledgeRequestData {
+        pub alert: dbt::AlertID
+    }
+#[derive(Serialize, Deserialize)]
+pub struct AlertsAcknowledgeResponseData {
+    pub alert: dbt::Alert
+}
+
+//////////////////////////////////////////////////
+// Categories
+
+#[derive(Serialize, Deserialize)]
+pub struct CategoriesResponseData {
+    pub categories: Vec<dbt::Category>
+}
+
+
+    #[derive(Serialize, Deserialize)]
+    pub struct CategoriesInsertRequestData {
+        pub category: dbt::Category
+    }
+
+//////////////////////////////////////////////////
+// Tax rates
+
+#[derive(Serialize, Deserialize)]
+pub struct TaxRatesResponseData {
+    pub tax_rates: Vec<dbt::TaxRate>
+}
+
+
+    #[derive(Serialize, Deserialize)]
+    pub struct TaxRatesInsertRequestData {
+        pub tax_rate: dbt::TaxRate
+    }
+
 //////////////////////////////////////////////////
 // Orders
 
     #[derive(Serialize, Deserialize)]
     pub struct OrdersRequestData {
         pub new: bool,
-        pub table: Option<VirtualTableID>
+        pub table: Option<VirtualTableID>,
+        /// Only orders of the open sessions, i.e. of the guests who
+        /// are at the tables right now.
+        #[serde(default)]
+        pub current: bool
     }
 #[derive(Serialize, Deserialize)]
 pub struct OrdersResponseData {
-    pub orders: Vec<dbt::Order>
+    pub orders: Vec<TotaledOrder>,
+    pub tables: Vec<dbt::TableTotals>
+}
+
+/// An order together with the totals computed by the server, the
+/// order's own fields stay at the top level.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct TotaledOrder {
+    #[serde(flatten)]
+    pub order: dbt::Order,
+    pub totals: dbt::OrderTotals
+}
+
+impl From<dbt::Order> for TotaledOrder {
+    fn from(order: dbt::Order) -> Self {
+        let totals = totals::order_totals(&order);
+        TotaledOrder { order, totals }
+    }
 }
 
 
@@ -117,16 +380,52 @@ pub struct OrdersResponseData {
     }
 #[derive(Serialize, Deserialize)]
 pub struct OrdersSpecificResponseData {
-    pub order: dbt::Order
+    pub order: TotaledOrder
 }
 
 
     #[derive(Serialize, Deserialize)]
     pub struct OrdersInsertRequestData {
         pub order: dbt::Order,
+        /// Token from the guest link of the table, needed unless the
+        /// order is placed by staff.
+        #[serde(default)]
+        pub token: Option<String>,
     }
 #[derive(Serialize, Deserialize)]
-pub struct OrdersInsertResponseData;
+pub struct OrdersInsertResponseData {
+    pub order: dbt::OrderID
+}
+
+/// What is wrong with a single line (or the whole) of a rejected order.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(tag = "kind")]
+pub enum OrderProblem {
+    UnknownTable,
+    EmptyOrder,
+    UnknownOffer,
+    Unavailable,
+    InvalidCount { min: u32, max: u32 },
+    InvalidNote { reason: String },
+    /// Not enough of a stock item the offer is made of.
+    OutOfStock { stock: dbt::StockItemID },
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct OrderLineError {
+    /// Index into the submitted `items`, `None` if the problem
+    /// is with the order as a whole.
+    pub line: Option<usize>,
+    pub offer: Option<dbt::OfferID>,
+    pub problem: OrderProblem,
+}
+
+/// Body of the `400 Bad Request` returned by `POST /orders` when
+/// the order did not pass validation.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct OrdersInsertErrorData {
+    pub errors: Vec<OrderLineError>
+}
 
 
     #[derive(Serialize, Deserialize)]
@@ -136,6 +435,379 @@ pub struct OrdersInsertResponseData;
 #[derive(Serialize, Deserialize)]
 pub struct OrdersDeleteResponseData;
 
+//////////////////////////////////////////////////
+// Promotions
+
+#[derive(Serialize, Deserialize)]
+pub struct PromotionsResponseData {
+    pub promotions: Vec<dbt::Promotion>
+}
+
+
+    #[derive(Serialize, Deserialize)]
+    pub struct PromotionsInsertRequestData {
+        pub promotion: dbt::Promotion
+    }
+
+//////////////////////////////////////////////////
+// Stock
+
+#[derive(Serialize, Deserialize)]
+pub struct StockResponseData {
+    pub stock: Vec<dbt::StockItem>
+}
+
+
+    #[derive(Serialize, Deserialize)]
+    pub struct StockInsertRequestData {
+        pub item: dbt::StockItem
+    }
+
+    /// Adds a delivery to the stock, `quantity` has to be positive.
+    #[derive(Serialize, Deserialize)]
+    pub struct StockReceiveRequestData {
+        pub stock:    dbt::StockItemID,
+        pub quantity: i64
+    }
+
+    /// Corrects the stock by `quantity`, negative for waste and
+    /// breakage, or sets it to the counted amount if `counted` is
+    /// given instead.
+    #[derive(Serialize, Deserialize)]
+    pub struct StockAdjustRequestData {
+        pub stock:    dbt::StockItemID,
+        #[serde(default)]
+        pub quantity: i64,
+        pub counted:  Option<i64>,
+        pub reason:   Option<String>
+    }
+
+#[derive(Serialize, Deserialize)]
+pub struct StockChangeResponseData {
+    pub item: dbt::StockItem
+}
+
+    /// Takes a guest order over as the logged in staff member.
+    #[derive(Serialize, Deserialize)]
+    pub struct OrdersAcceptRequestData {
+        pub order: dbt::OrderID
+    }
+
+/// Unpaid orders in the section of the logged in staff member and
+/// the ones they accepted elsewhere.
+#[derive(Serialize, Deserialize)]
+pub struct MyOrdersResponseData {
+    pub orders: Vec<TotaledOrder>
+}
+
+    /// Moves a single unpaid order to another table.
+    #[derive(Serialize, Deserialize)]
+    pub struct OrdersMoveRequestData {
+        pub order: dbt::OrderID,
+        pub to: VirtualTableID
+    }
+
+    /// Moves the unpaid orders of a session to another table.
+    #[derive(Serialize, Deserialize)]
+    pub struct SessionsMoveRequestData {
+        pub session: dbt::SessionID,
+        pub to: VirtualTableID
+    }
+
+    /// Moves all unpaid orders of `from` over to `into`.
+    #[derive(Serialize, Deserialize)]
+    pub struct TablesMergeRequestData {
+        pub from: VirtualTableID,
+        pub into: VirtualTableID
+    }
+
+/// The old and new id of a moved order.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct MovedOrder {
+    pub from: dbt::OrderID,
+    pub to: dbt::OrderID
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct OrdersMoveResponseData {
+    pub moved: Vec<MovedOrder>
+}
+
+//////////////////////////////////////////////////
+// Reservations
+
+#[derive(Serialize, Deserialize)]
+pub struct ReservationsResponseData {
+    pub date: String,
+    pub reservations: Vec<dbt::Reservation>
+}
+
+
+    #[derive(Serialize, Deserialize)]
+    pub struct ReservationsInsertRequestData {
+        pub reservation: dbt::Reservation
+    }
+#[derive(Serialize, Deserialize)]
+pub struct ReservationsInsertResponseData {
+    pub reservation: dbt::Reservation
+}
+
+/// Body of the `409 Conflict` returned when a reservation can't be
+/// booked.
+#[derive(Serialize, Deserialize)]
+pub struct ReservationsConflictData {
+    pub conflicts: Vec<String>
+}
+
+
+    /// Marks arrivals, no-shows and cancellations.
+    #[derive(Serialize, Deserialize)]
+    pub struct ReservationsStatusRequestData {
+        pub reservation: dbt::ReservationID,
+        pub status: dbt::ReservationStatus
+    }
+
+//////////////////////////////////////////////////
+// Sessions
+
+#[derive(Serialize, Deserialize)]
+pub struct SessionsResponseData {
+    pub sessions: Vec<dbt::Session>
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct SessionsSpecificResponseData {
+    pub session: dbt::Session,
+    pub orders: Vec<TotaledOrder>,
+    pub total: dbt::Amount
+}
+
+//////////////////////////////////////////////////
+// Staff
+
+/// A staff account as it is sent out, without the secret.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct StaffAccount {
+    pub name: dbt::StaffID,
+    pub role: dbt::Role,
+    pub authority: Authority,
+    /// Everything the account is allowed, from the role and granted.
+    pub permissions: Vec<dbt::Permission>,
+    pub disabled: bool,
+    pub tables: Vec<VirtualTableID>,
+    pub zones: Vec<String>
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct StaffResponseData {
+    pub staff: Vec<StaffAccount>
+}
+
+
+    /// Creates or updates an account. `secret` is the new PIN or
+    /// password, it can be left out when updating to keep the old one.
+    #[derive(Serialize, Deserialize)]
+    pub struct StaffInsertRequestData {
+        pub name: dbt::StaffID,
+        pub role: dbt::Role,
+        #[serde(default)]
+        pub permissions: Vec<dbt::Permission>,
+        #[serde(default)]
+        pub disabled: bool,
+        pub secret: Option<String>
+    }
+
+
+    /// Replaces the section of the staff member.
+    #[derive(Serialize, Deserialize)]
+    pub struct StaffAssignRequestData {
+        pub staff: dbt::StaffID,
+        #[serde(default)]
+        pub tables: Vec<VirtualTableID>,
+        #[serde(default)]
+        pub zones: Vec<String>
+    }
+
+
+    #[derive(Serialize, Deserialize)]
+    pub struct LoginRequestData {
+        pub name: dbt::StaffID,
+        pub secret: String
+    }
+#[derive(Serialize, Deserialize)]
+pub struct LoginResponseData {
+    pub token: String,
+    pub expires_at: i64,
+    pub staff: StaffAccount
+}
+
+//////////////////////////////////////////////////
+// Bills
+
+/// A bill with everything needed to show it and take payments
+/// for it.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct BillSummary {
+    pub bill: dbt::Bill,
+    pub orders: Vec<TotaledOrder>,
+    pub payments: Vec<dbt::Payment>,
+    pub taxes: Vec<dbt::TaxBreakdown>,
+    pub total: dbt::Amount,
+    pub paid: dbt::Amount,
+    pub remaining: dbt::Amount,
+    pub tips: dbt::Amount,
+}
+
+
+#[derive(Serialize, Deserialize)]
+pub struct BillsResponseData {
+    pub bills: Vec<BillSummary>
+}
+
+
+#[derive(Serialize, Deserialize)]
+pub struct BillsSpecificResponseData {
+    pub bill: BillSummary
+}
+
+
+    /// Opens a bill for all unpaid orders of the table, if the table
+    /// already has an open bill, new orders are added to it instead.
+    #[derive(Serialize, Deserialize)]
+    pub struct BillsOpenRequestData {
+        pub table: VirtualTableID
+    }
+#[derive(Serialize, Deserialize)]
+pub struct BillsOpenResponseData {
+    pub bill: BillSummary
+}
+
+
+    /// Pays (part of) a bill.
+    ///
+    /// If `items` is not empty the amount is what those items cost,
+    /// otherwise `amount` is used, and if that is missing as well the
+    /// payment covers everything that remains.
+    #[derive(Serialize, Deserialize)]
+    pub struct BillsPayRequestData {
+        pub bill: dbt::BillID,
+        pub method: dbt::PaymentMethod,
+        pub amount: Option<dbt::Amount>,
+        #[serde(default)]
+        pub items: Vec<dbt::PaidItem>,
+        #[serde(default)]
+        pub tip: dbt::Amount,
+        pub tendered: Option<dbt::Amount>
+    }
+#[derive(Serialize, Deserialize)]
+pub struct BillsPayResponseData {
+    pub payment: dbt::Payment,
+    pub bill: BillSummary
+}
+
+
+#[derive(Serialize, Deserialize)]
+pub struct BillsSplitResponseData {
+    /// What each of the guests should pay so that together they
+    /// cover the remaining amount.
+    pub shares: Vec<dbt::Amount>
+}
+
+
+    #[derive(Serialize, Deserialize)]
+    pub struct BillsCloseRequestData {
+        pub bill: dbt::BillID
+    }
+#[derive(Serialize, Deserialize)]
+pub struct BillsCloseResponseData {
+    pub bill: BillSummary
+}
+
+//////////////////////////////////////////////////
+// Receipts
+
+    /// Issues a receipt for either a closed bill or a single paid
+    /// order, issuing it twice returns the same receipt. An order is
+    /// only ever on one receipt, the bill's or its own.
+    #[derive(Serialize, Deserialize)]
+    pub struct ReceiptsIssueRequestData {
+        pub bill: Option<dbt::BillID>,
+        pub order: Option<dbt::OrderID>
+    }
+#[derive(Serialize, Deserialize)]
+pub struct ReceiptsIssueResponseData {
+    pub receipt: dbt::Receipt
+}
+
+
+#[derive(Serialize, Deserialize)]
+pub struct ReceiptsSpecificResponseData {
+    pub receipt: dbt::Receipt
+}
+
+//////////////////////////////////////////////////
+// Venue
+
+#[derive(Serialize, Deserialize)]
+pub struct VenueResponseData {
+    pub venue: dbt::Venue
+}
+
+
+    #[derive(Serialize, Deserialize)]
+    pub struct VenueInsertRequestData {
+        pub venue: dbt::Venue
+    }
+
+//////////////////////////////////////////////////
+// Fiscalization
+
+#[derive(Serialize, Deserialize)]
+pub struct FiscalResponseData {
+    pub settings: dbt::FiscalSettings
+}
+
+
+    #[derive(Serialize, Deserialize)]
+    pub struct FiscalInsertRequestData {
+        pub settings: dbt::FiscalSettings
+    }
+
+#[derive(Serialize, Deserialize)]
+pub struct FiscalPendingResponseData {
+    pub receipts: Vec<dbt::Receipt>
+}
+
+/// Outcome of sending the pending receipts again.
+#[derive(Serialize, Deserialize)]
+pub struct FiscalRetryResponseData {
+    pub sent:    usize,
+    pub pending: usize
+}
+
+//////////////////////////////////////////////////
+// Reports
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct MethodTotal {
+    pub method: dbt::PaymentMethod,
+    pub amount: dbt::Amount,
+    pub tips: dbt::Amount,
+}
+
+/// Sales of a single day, counted from the bills closed that day.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct ReportsDailyResponseData {
+    /// `YYYY-MM-DD` in the local time of the venue.
+    pub date: String,
+    pub bills: u32,
+    pub total: dbt::Amount,
+    pub tips: dbt::Amount,
+    pub methods: Vec<MethodTotal>,
+    pub lines: Vec<dbt::LineTotal>,
+    pub taxes: Vec<dbt::TaxBreakdown>,
+}
+
 //////////////////////////////////////////////////
 // Custom
 
@@ -164,6 +836,7 @@ pub enum RequestKind {
     TablesSpecific,
     TablesInsert,
     TablesDelete,
+    TablesBill,
 
     Offers,
     OffersSpecific,
@@ -183,11 +856,14 @@ pub enum RequestKind {
 pub struct Request {
 
     pub kind: RequestKind,
-    pub payload: Option<serde_json::Value>
+    pub payload: Option<serde_json::Value>,
+    /// Token from `POST /login`, sent as a bearer token.
+    pub token: Option<String>
 
 }
 
 
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
 pub enum Authority {
     User,
     Admin
@@ -220,7 +896,7 @@ impl Request {
             .add(b'/')
             .add(b'=');
 
-        let (request, body) = match self.kind {
+        let (mut request, body) = match self.kind {
 
             RequestKind::Tables => {
                 let uri = format!("{}/{}",
@@ -337,6 +1013,34 @@ impl Request {
                 )                    
             }
 
+            RequestKind::TablesBill => {
+                let uri = format!("{}/{}/bill",
+                    address,
+                    utf8_percent_encode(
+                        format!(
+                            "tables-{}",
+                            id
+                        ).as_str(), 
+                        QUERY_ENCODE_SET
+                    )
+                );
+                log::info!("{}", uri);
+                (
+                    hyper::Request::builder()
+                        .method(hyper::Method::GET)
+                        .uri(uri)
+                        .header("Accept", "*/*")
+                        .header("User-Agent", "Rust HTTP Client")
+                        .header("Access-Control-Allow-Origin", "*")
+                        .body(())
+                        .unwrap(),
+                    match self.payload.clone() {
+                        Some(payload) => payload.to_string(),
+                        None => "".to_string()
+                    }                
+                )                    
+            }
+
             RequestKind::Offers => {
                 let uri = format!("{}/{}",
                     address,
@@ -660,6 +1364,13 @@ impl Request {
             //     "".to_string()
             // )  
         };
+
+        if let Some(token) = &self.token {
+            request.headers_mut().insert(
+                hyper::header::AUTHORIZATION,
+                format!("Bearer {}", token).parse().expect("Token is not a valid header value.")
+            );
+        }
         
         let request_bytes = format!(
             "{} {} {}\r\n{}\r\n\r\n{}",
@@ -748,6 +1459,7 @@ impl Request {
                 return match self.kind {
                     RequestKind::Tables         |
                     RequestKind::TablesSpecific |
+                    RequestKind::TablesBill     |
                     RequestKind::Offers         |
                     RequestKind::OffersSpecific | 
                     RequestKind::Orders         |
@@ -0,0 +1,148 @@
+use std::collections::BTreeMap;
+
+use super::dbt;
+
+/// Totals of a single order line, using the price snapshot taken
+/// when the order was placed.
+pub fn line_total(item: &dbt::OrderItem) -> dbt::LineTotal {
+
+    let unit = dbt::Amount {
+        integer: item.price_integer,
+        fraction: item.price_fraction
+    };
+
+    let gross = unit.cents() * item.count as u64;
+    let discount = item.discounts
+        .iter()
+        .map(|discount| discount.amount.cents())
+        .sum::<u64>()
+        .min(gross);
+
+    dbt::LineTotal {
+        id: item.id.clone(),
+        name: item.name.clone(),
+        count: item.count,
+        unit,
+        discount: dbt::Amount::from_cents(discount),
+        total: dbt::Amount::from_cents(gross - discount),
+        tax: item.tax.clone()
+    }
+
+}
+
+pub fn order_totals(order: &dbt::Order) -> dbt::OrderTotals {
+
+    let lines = order.items
+        .iter()
+        .map(line_total)
+        .collect::<Vec<dbt::LineTotal>>();
+
+    let taxes = tax_breakdown(&lines);
+    let total = dbt::Amount::from_cents(
+        lines.iter().map(|line| line.total.cents()).sum()
+    );
+
+    dbt::OrderTotals { lines, taxes, total }
+
+}
+
+/// Sums up the given orders per table, tables are returned sorted
+/// by name.
+pub fn table_totals(orders: &[dbt::Order]) -> Vec<dbt::TableTotals> {
+
+    let mut tables: BTreeMap<dbt::VirtualTableID, (u32, u64)> = BTreeMap::new();
+
+    for order in orders {
+        let entry = tables.entry(order.id.table.clone()).or_default();
+        entry.0 += 1;
+        entry.1 += order_totals(order).total.cents();
+    }
+
+    tables
+        .into_iter()
+        .map(|(table, (order_count, cents))| dbt::TableTotals {
+            table,
+            order_count,
+            total: dbt::Amount::from_cents(cents)
+        })
+        .collect()
+
+}
+
+/// Merges the lines of all given orders into one line per offer and
+/// unit price, the way they are listed on a bill.
+pub fn merged_lines(orders: &[dbt::Order]) -> Vec<dbt::LineTotal> {
+
+    let mut lines: Vec<dbt::LineTotal> = Vec::new();
+
+    for line in orders.iter().flat_map(|order| order.items.iter().map(line_total)) {
+        match lines
+            .iter_mut()
+            .find(|merged| {
+                merged.id == line.id
+                && merged.unit == line.unit
+                && merged.tax == line.tax
+            })
+        {
+            Some(merged) => {
+                merged.count += line.count;
+                merged.discount = dbt::Amount::from_cents(
+                    merged.discount.cents() + line.discount.cents()
+                );
+                merged.total = dbt::Amount::from_cents(
+                    merged.total.cents() + line.total.cents()
+                );
+            }
+            None => lines.push(line)
+        }
+    }
+
+    lines
+
+}
+
+/// The tax contained in a gross amount, prices include tax so the
+/// tax is `gross * rate / (100% + rate)`, rounded to the nearest cent.
+pub fn included_tax(gross: dbt::Amount, basis_points: u32) -> dbt::Amount {
+
+    let gross = gross.cents() as u128;
+    let basis_points = basis_points as u128;
+    let divisor = 10000 + basis_points;
+
+    dbt::Amount::from_cents(
+        ((gross * basis_points * 2 + divisor) / (divisor * 2)) as u64
+    )
+
+}
+
+/// Sums the lines up per tax rate and splits each sum into net and
+/// tax, rates are returned in the order they first appear.
+pub fn tax_breakdown(lines: &[dbt::LineTotal]) -> Vec<dbt::TaxBreakdown> {
+
+    let mut gross_per_rate: Vec<(Option<dbt::TaxRate>, u64)> = Vec::new();
+
+    for line in lines {
+        match gross_per_rate.iter_mut().find(|(rate, _)| rate == &line.tax) {
+            Some((_, gross)) => *gross += line.total.cents(),
+            None => gross_per_rate.push((line.tax.clone(), line.total.cents()))
+        }
+    }
+
+    gross_per_rate
+        .into_iter()
+        .map(|(rate, gross)| {
+            let gross = dbt::Amount::from_cents(gross);
+            let tax = match &rate {
+                Some(rate) => included_tax(gross, rate.basis_points),
+                None => dbt::Amount::default()
+            };
+            dbt::TaxBreakdown {
+                rate,
+                net: dbt::Amount::from_cents(gross.cents() - tax.cents()),
+                tax,
+                gross
+            }
+        })
+        .collect()
+
+}
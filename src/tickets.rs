@@ -0,0 +1,248 @@
+use crate::clock;
+use crate::db;
+use crate::db::DatabaseElement;
+use crate::shared::dbt as dbt;
+use crate::shared::req_resp as req;
+
+/// The station preparing an offer, the offer's own station wins over
+/// the station of its category. `None` for offers that need no
+/// preparation.
+pub fn station_of(offer: &dbt::Offer, categories: &[dbt::Category]) -> Option<dbt::StationID> {
+    offer.station.clone().or_else(|| {
+        categories
+            .iter()
+            .find(|category| Some(&category.name) == offer.category.as_ref())
+            .and_then(|category| category.station.clone())
+    })
+}
+
+/// Splits a stored order into one ticket per station and stores them.
+pub fn route(
+    order: &dbt::Order,
+    offers: &[dbt::Offer],
+    categories: &[dbt::Category],
+    db: &sled::Db
+) -> Result<Vec<dbt::Ticket>, String> {
+
+    let now = clock::timestamp();
+    let mut tickets: Vec<dbt::Ticket> = Vec::new();
+
+    for (line, item) in order.items.iter().enumerate() {
+
+        let station = match offers
+            .iter()
+            .find(|offer| offer.name == item.id)
+            .and_then(|offer| station_of(offer, categories))
+        {
+            Some(station) => station,
+            None => continue
+        };
+
+        let ticket_line = dbt::TicketLine {
+            line,
+            name: item.name.clone(),
+            count: item.count,
+            note: item.note.clone(),
+            status: dbt::TicketStatus::Queued
+        };
+
+        match tickets.iter_mut().find(|ticket| ticket.station == station) {
+            Some(ticket) => ticket.lines.push(ticket_line),
+            None => tickets.push(dbt::Ticket {
+                id: db.generate_id().map_err(|err| err.to_string())?,
+                order: order.id.clone(),
+                station,
+                lines: vec![ticket_line],
+                note: order.note.clone(),
+                status: dbt::TicketStatus::Queued,
+                created_at: now,
+                updated_at: now
+            })
+        }
+
+    }
+
+    for ticket in tickets.iter() {
+        ticket.insert(db)?;
+    }
+
+    Ok(tickets)
+
+}
+
+pub fn ticket_by_id(id: dbt::TicketID, db: &sled::Db) -> Result<Option<dbt::Ticket>, String> {
+    Ok(
+        dbt::Ticket::get_all(db)?
+            .into_iter()
+            .find(|ticket| ticket.id == id)
+    )
+}
+
+pub fn tickets_of_order(order: &dbt::OrderID, db: &sled::Db) -> Result<Vec<dbt::Ticket>, String> {
+    Ok(
+        dbt::Ticket::get_all(db)?
+            .into_iter()
+            .filter(|ticket| &ticket.order == order)
+            .collect()
+    )
+}
+
+/// Tickets of the station still being worked on, oldest first, or
+/// the served ones if `served` is set.
+pub fn queue(station: &dbt::StationID, served: bool, db: &sled::Db) -> Result<Vec<dbt::Ticket>, String> {
+
+    let mut tickets = dbt::Ticket {
+        station: station.clone(),
+        status: if served {dbt::TicketStatus::Served} else {dbt::TicketStatus::Queued},
+        ..Default::default()
+    }
+    .get_templated(db)?
+    .into_iter()
+    .filter(|ticket| &ticket.station == station)
+    .collect::<Vec<dbt::Ticket>>();
+
+    tickets.sort_by_key(|ticket| (ticket.created_at, ticket.id));
+
+    Ok(tickets)
+
+}
+
+/// Sets the status of one line of the ticket, or of all of them if
+/// `line` is `None`, and stores the ticket. Without a `status` the
+/// line (or the whole ticket) moves on to the next one.
+pub fn bump(
+    mut ticket: dbt::Ticket,
+    line: Option<usize>,
+    status: Option<dbt::TicketStatus>,
+    db: &sled::Db
+) -> Result<Result<dbt::Ticket, String>, String> {
+
+    match line {
+        Some(line) => {
+            let ticket_line = match ticket.lines.iter_mut().find(|ticket_line| ticket_line.line == line) {
+                Some(ticket_line) => ticket_line,
+                None => return Ok(Err("Line is not on the ticket.".to_string()))
+            };
+            ticket_line.status = match status.or_else(|| ticket_line.status.next()) {
+                Some(status) => status,
+                None => return Ok(Err("Line is already served.".to_string()))
+            };
+        }
+        None => {
+            let explicit = status.is_some();
+            let status = match status.or_else(|| ticket.status.next()) {
+                Some(status) => status,
+                None => return Ok(Err("Ticket is already served.".to_string()))
+            };
+            // Bumping leaves the lines that are ahead alone.
+            for ticket_line in ticket.lines.iter_mut() {
+                if explicit || ticket_line.status < status {
+                    ticket_line.status = status;
+                }
+            }
+        }
+    }
+
+    ticket.remove(db)?;
+
+    ticket.status = ticket.lines
+        .iter()
+        .map(|ticket_line| ticket_line.status)
+        .min()
+        .unwrap_or(dbt::TicketStatus::Served);
+    ticket.updated_at = clock::timestamp();
+
+    ticket.insert(db)?;
+
+    Ok(Ok(ticket))
+
+}
+
+/// Marks the order finished by `staff` once all of its tickets are
+/// served, returns whether it did.
+pub fn finish_if_served(order: &dbt::OrderID, staff: &dbt::StaffID, db: &sled::Db) -> Result<bool, String> {
+
+    if tickets_of_order(order, db)?.iter().any(|ticket| ticket.status != dbt::TicketStatus::Served) {
+        return Ok(false)
+    }
+
+    match db::order_by_id(order, db)? {
+        Some(mut order) if !order.finished => {
+            // Orders are kept under whether they are finished.
+            order.remove(db)?;
+            order.finished = true;
+            order.served_by = Some(staff.clone());
+            order.insert(db)?;
+            Ok(true)
+        }
+        _ => Ok(false)
+    }
+
+}
+
+/// The order as the guests see it. Lines no station prepares are
+/// received until the order is finished, the order is as far along
+/// as its slowest prepared line.
+pub fn guest_order(order: &dbt::Order, tickets: &[dbt::Ticket]) -> req::GuestOrder {
+
+    let prepared = |line: usize| tickets
+        .iter()
+        .filter(|ticket| ticket.order == order.id)
+        .flat_map(|ticket| ticket.lines.iter())
+        .find(|ticket_line| ticket_line.line == line)
+        .map(|ticket_line| req::GuestOrderStatus::from(ticket_line.status));
+
+    let lines = order.items
+        .iter()
+        .enumerate()
+        .map(|(line, item)| req::GuestOrderLine {
+            name: item.name.clone(),
+            count: item.count,
+            status: match prepared(line) {
+                _ if order.finished => req::GuestOrderStatus::Served,
+                Some(status) => status,
+                None => req::GuestOrderStatus::Received
+            }
+        })
+        .collect::<Vec<req::GuestOrderLine>>();
+
+    let status = if order.finished {
+        req::GuestOrderStatus::Served
+    } else {
+        (0..order.items.len())
+            .filter_map(prepared)
+            .min()
+            .unwrap_or(req::GuestOrderStatus::Received)
+    };
+
+    req::GuestOrder {
+        id: order.id.clone(),
+        status,
+        lines
+    }
+
+}
+
+/// Marks every ticket of the order served, for orders finished
+/// without going through the stations.
+pub fn serve_order(order: &dbt::OrderID, db: &sled::Db) -> Result<(), String> {
+
+    for ticket in tickets_of_order(order, db)? {
+        if ticket.status != dbt::TicketStatus::Served {
+            bump(ticket, None, Some(dbt::TicketStatus::Served), db)??;
+        }
+    }
+
+    Ok(())
+
+}
+
+pub fn remove_of_order(order: &dbt::OrderID, db: &sled::Db) -> Result<(), String> {
+
+    for ticket in tickets_of_order(order, db)? {
+        ticket.remove(db)?;
+    }
+
+    Ok(())
+
+}
@@ -0,0 +1,199 @@
+use crate::billing;
+use crate::billing::BillingError;
+use crate::clock;
+use crate::db::DatabaseElement;
+use crate::session;
+use crate::shared::dbt as dbt;
+use crate::shared::req_resp as req;
+
+/// Moves unpaid orders to another table.
+///
+/// Orders are stored under their table and count, so every order is
+/// re-keyed under a new count of the target table and joins the
+/// target's open session (or a new one), taking its tickets along.
+/// Sessions left without any unpaid orders are closed, and orders are
+/// taken off open bills that have nothing paid on them yet. All
+/// writes go to the database in one batch, so a failure can't leave
+/// an order under both tables.
+pub fn move_orders(
+    mut orders: Vec<dbt::Order>,
+    to: &dbt::VirtualTableID,
+    db: &sled::Db
+) -> Result<Vec<req::MovedOrder>, BillingError> {
+
+    let mut target = match dbt::VirtualTable::get(
+        dbt::VirtualTable {
+            name: to.clone(),
+            ..Default::default()
+        }.qualified_identifier(),
+        db
+    )? {
+        Some(table) => table,
+        None => return Err(BillingError::NotFound("Table does not exist!".to_string()))
+    };
+
+    if orders.is_empty() {
+        return Err(BillingError::Invalid("There are no orders to move.".to_string()))
+    }
+
+    for order in orders.iter() {
+        if order.paid {
+            return Err(BillingError::Invalid(format!(
+                "Order {} of `{}` is already paid.", order.id.count, order.id.table
+            )))
+        }
+        if &order.id.table == to {
+            return Err(BillingError::Invalid(format!(
+                "Order {} is already at `{}`.", order.id.count, to
+            )))
+        }
+    }
+
+    let mut batch = sled::Batch::default();
+
+    // Take the orders off open bills, unless something was paid on
+    // them already.
+    let mut bills = dbt::Bill {
+        closed: false,
+        ..Default::default()
+    }.get_status(db)?;
+
+    for bill in bills.iter_mut() {
+        let before = bill.orders.len();
+        bill.orders.retain(|id| !orders.iter().any(|order| &order.id == id));
+        if bill.orders.len() == before {
+            continue
+        }
+        if !billing::payments_of_bill(bill.id, db)?.is_empty() {
+            return Err(BillingError::Invalid(format!(
+                "Bill {} of `{}` is partly paid, settle it first.", bill.id, bill.table
+            )))
+        }
+        if bill.orders.is_empty() {
+            bill.remove_batched(&mut batch);
+        } else {
+            bill.insert_batched(&mut batch)?;
+        }
+    }
+
+    let target_session = match session::open_session_of_table(to, db)? {
+        Some(session) => session,
+        None => {
+            let session = dbt::Session {
+                id: db.generate_id().map_err(|err| err.to_string())?,
+                table: to.clone(),
+                closed: false,
+                opened_at: clock::timestamp(),
+                closed_at: None
+            };
+            session.insert_batched(&mut batch)?;
+            session
+        }
+    };
+
+    // Counts are handed out after the highest one in use, in case the
+    // table's counter is behind its orders.
+    let all_orders = dbt::Order::get_all(db)?;
+    target.order_count = all_orders
+        .iter()
+        .filter(|order| &order.id.table == to)
+        .map(|order| order.id.count)
+        .max()
+        .unwrap_or(0)
+        .max(target.order_count);
+
+    orders.sort_by(|a, b| (&a.id.table, a.id.count).cmp(&(&b.id.table, b.id.count)));
+
+    let tickets = dbt::Ticket::get_all(db)?;
+
+    let mut moved = Vec::new();
+    let mut sources: Vec<dbt::VirtualTableID> = Vec::new();
+    let mut left_sessions: Vec<dbt::SessionID> = Vec::new();
+
+    for order in orders.iter() {
+
+        order.remove_batched(&mut batch);
+
+        target.order_count += 1;
+        let mut order_moved = order.clone();
+        order_moved.id = dbt::OrderID {
+            table: to.clone(),
+            count: target.order_count
+        };
+        order_moved.session = Some(target_session.id);
+        order_moved.insert_batched(&mut batch)?;
+
+        for ticket in tickets.iter().filter(|ticket| ticket.order == order.id) {
+            let mut ticket = ticket.clone();
+            ticket.order = order_moved.id.clone();
+            ticket.insert_batched(&mut batch)?;
+        }
+
+        if !sources.contains(&order.id.table) {
+            sources.push(order.id.table.clone());
+        }
+        if let Some(session) = order.session {
+            if session != target_session.id && !left_sessions.contains(&session) {
+                left_sessions.push(session);
+            }
+        }
+
+        moved.push(req::MovedOrder {
+            from: order.id.clone(),
+            to: order_moved.id
+        });
+
+    }
+
+    if target.state == dbt::TableState::Free {
+        target.state = dbt::TableState::Occupied;
+    }
+    target.insert_batched(&mut batch)?;
+
+    let staying = |order: &&dbt::Order| {
+        !order.paid && !orders.iter().any(|moving| moving.id == order.id)
+    };
+
+    for id in left_sessions {
+        if let Some(mut left) = session::session_by_id(id, db)? {
+            if !left.closed && !all_orders.iter().filter(staying).any(|order| order.session == Some(id)) {
+                left.remove_batched(&mut batch);
+                left.closed = true;
+                left.closed_at = Some(clock::timestamp());
+                left.insert_batched(&mut batch)?;
+            }
+        }
+    }
+
+    for source in sources {
+        let empty = !all_orders.iter().filter(staying).any(|order| order.id.table == source);
+        let table = dbt::VirtualTable::get(
+            dbt::VirtualTable {
+                name: source,
+                ..Default::default()
+            }.qualified_identifier(),
+            db
+        )?;
+        if let Some(mut table) = table {
+            if empty && table.state == dbt::TableState::Occupied {
+                table.state = dbt::TableState::NeedsCleaning;
+                table.insert_batched(&mut batch)?;
+            }
+        }
+    }
+
+    db.apply_batch(batch).map_err(|err| err.to_string())?;
+
+    Ok(moved)
+
+}
+
+/// Unpaid orders of a table.
+pub fn open_orders_of_table(table: &dbt::VirtualTableID, db: &sled::Db) -> Result<Vec<dbt::Order>, String> {
+    Ok(
+        dbt::Order::get_all(db)?
+            .into_iter()
+            .filter(|order| &order.id.table == table && !order.paid)
+            .collect()
+    )
+}
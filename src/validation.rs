@@ -0,0 +1,154 @@
+use std::collections::HashMap;
+
+use crate::shared::dbt as dbt;
+use crate::shared::req_resp as req;
+
+/// Checks an incoming order against the current offers and cleans
+/// it up so it can be stored as is.
+///
+/// Every line is checked on its own so that the client gets the full
+/// list of problems back in one go:
+/// - the offer has to exist and be available at the given time,
+/// - the count has to be between 1 and [`dbt::ORDER_ITEM_MAX_COUNT`],
+/// - the note has to pass [`dbt::sanitize_note`].
+///
+/// If all lines are fine, lines that order the same offer with the
+/// same note are merged into one and the merged count is checked
+/// against the limit again.
+pub fn validate_order(
+    order: &mut dbt::Order,
+    offers: &[dbt::Offer],
+    (day, time): (dbt::Weekday, dbt::TimeOfDay)
+) -> Result<(), req::OrdersInsertErrorData> {
+
+    let mut errors: Vec<req::OrderLineError> = Vec::new();
+
+    match dbt::sanitize_note(order.note.take()) {
+        Ok(note) => order.note = note,
+        Err(reason) => errors.push(req::OrderLineError {
+            line: None,
+            offer: None,
+            problem: req::OrderProblem::InvalidNote { reason }
+        })
+    }
+
+    if order.items.is_empty() {
+        errors.push(req::OrderLineError {
+            line: None,
+            offer: None,
+            problem: req::OrderProblem::EmptyOrder
+        });
+    }
+
+    let count_problem = req::OrderProblem::InvalidCount {
+        min: 1,
+        max: dbt::ORDER_ITEM_MAX_COUNT
+    };
+
+    for (line, item) in order.items.iter_mut().enumerate() {
+
+        let mut push = |problem| errors.push(req::OrderLineError {
+            line: Some(line),
+            offer: Some(item.id.clone()),
+            problem
+        });
+
+        match offers.iter().find(|offer| offer.name == item.id) {
+            None => push(req::OrderProblem::UnknownOffer),
+            Some(offer) if !offer.is_available_at(day, time) => {
+                push(req::OrderProblem::Unavailable)
+            }
+            Some(_) => ()
+        }
+
+        if item.count == 0 || item.count > dbt::ORDER_ITEM_MAX_COUNT {
+            push(count_problem.clone());
+        }
+
+        match dbt::sanitize_note(item.note.take()) {
+            Ok(note) => item.note = note,
+            Err(reason) => push(req::OrderProblem::InvalidNote { reason })
+        }
+
+    }
+
+    if !errors.is_empty() {
+        return Err(req::OrdersInsertErrorData { errors })
+    }
+
+    let mut merged: Vec<dbt::OrderItem> = Vec::new();
+    let mut first_line: Vec<usize> = Vec::new();
+    let mut positions: HashMap<(dbt::OfferID, Option<String>), usize> = HashMap::new();
+
+    for (line, item) in order.items.drain(..).enumerate() {
+        match positions.get(&(item.id.clone(), item.note.clone())) {
+            Some(&position) => merged[position].count += item.count,
+            None => {
+                positions.insert((item.id.clone(), item.note.clone()), merged.len());
+                first_line.push(line);
+                merged.push(item);
+            }
+        }
+    }
+
+    for (item, line) in merged.iter().zip(first_line) {
+        if item.count > dbt::ORDER_ITEM_MAX_COUNT {
+            errors.push(req::OrderLineError {
+                line: Some(line),
+                offer: Some(item.id.clone()),
+                problem: count_problem.clone()
+            });
+        }
+    }
+
+    order.items = merged;
+
+    if !errors.is_empty() {
+        return Err(req::OrdersInsertErrorData { errors })
+    }
+
+    Ok(())
+
+}
+
+/// The tax rate that applies to an offer, the offer's own rate wins
+/// over the rate of its category.
+pub fn effective_tax_rate(
+    offer: &dbt::Offer,
+    categories: &[dbt::Category],
+    tax_rates: &[dbt::TaxRate]
+) -> Option<dbt::TaxRate> {
+
+    let name = offer.tax_rate.clone().or_else(|| {
+        categories
+            .iter()
+            .find(|category| Some(&category.name) == offer.category.as_ref())
+            .and_then(|category| category.tax_rate.clone())
+    })?;
+
+    tax_rates.iter().find(|rate| rate.name == name).cloned()
+
+}
+
+/// Copies the name, price and tax rate of each ordered offer into
+/// its order line.
+///
+/// Should only be called on an order that passed [`validate_order`],
+/// lines whose offer can't be found are left as they are.
+pub fn snapshot_offers(
+    order: &mut dbt::Order,
+    offers: &[dbt::Offer],
+    categories: &[dbt::Category],
+    tax_rates: &[dbt::TaxRate]
+) {
+
+    for item in order.items.iter_mut() {
+        if let Some(offer) = offers.iter().find(|offer| offer.name == item.id) {
+            item.name = offer.name.clone();
+            item.price_integer = offer.price_integer;
+            item.price_fraction = offer.price_fraction;
+            item.tax = effective_tax_rate(offer, categories, tax_rates);
+        }
+    }
+
+}